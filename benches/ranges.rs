@@ -0,0 +1,87 @@
+//! Compares building a `Ranges` one insert at a time against the old sorted
+//! `Vec` representation, which cloned, re-sorted and merged on every union.
+//!
+//! Run with `cargo bench --bench ranges`. The old way is quadratic and takes
+//! on the order of a minute.
+
+use std::time::{Duration, Instant};
+
+use adv2023::{Range, Ranges};
+
+const INSERTS: usize = 100_000;
+
+// The previous implementation, kept only to compare against.
+#[derive(Clone, Default)]
+struct VecRanges(Vec<(i64, i64)>);
+
+impl VecRanges {
+    fn merge(&mut self) {
+        let mut idx = 0;
+        while (idx + 1) < self.0.len() {
+            if self.0[idx].1 >= self.0[idx + 1].0 {
+                self.0[idx].1 = self.0[idx].1.max(self.0[idx + 1].1);
+                self.0.remove(idx + 1);
+            } else {
+                idx += 1;
+            }
+        }
+    }
+
+    fn union(&self, other: &VecRanges) -> VecRanges {
+        let mut new = self.clone();
+        new.0.extend(other.0.clone());
+        new.0.sort();
+        new.merge();
+        new
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|(from, to)| (to - from) as usize).sum()
+    }
+}
+
+// Pseudo-random ranges, mostly disjoint so the set keeps growing.
+fn ranges() -> Vec<(i64, i64)> {
+    let mut state = 0x2023_u64;
+    (0..INSERTS)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1);
+            let from = (state >> 33) as i64 % 1_000_000_000;
+            (from, from + (state >> 20) as i64 % 100 + 1)
+        })
+        .collect()
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+fn main() {
+    let input = ranges();
+
+    let (len, map_time) = time(|| {
+        let mut r = Ranges::empty();
+        for &(from, to) in &input {
+            r.insert(Range::new(from, to));
+        }
+        r.len()
+    });
+    println!("btree map: {INSERTS} inserts in {map_time:?}");
+
+    let (vec_len, vec_time) = time(|| {
+        let mut r = VecRanges::default();
+        for &(from, to) in &input {
+            r = r.union(&VecRanges(vec![(from, to)]));
+        }
+        r.len()
+    });
+    println!("sorted vec: {INSERTS} inserts in {vec_time:?}");
+
+    assert_eq!(len, vec_len);
+    println!(
+        "speedup: {:.0}x",
+        vec_time.as_secs_f64() / map_time.as_secs_f64()
+    );
+}
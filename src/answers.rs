@@ -0,0 +1,172 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::{runner::DayResult, Answer};
+
+/// Known-good answers, as stored in `answers.toml`.
+///
+/// Only the small subset of TOML needed here is understood:
+///
+/// ```toml
+/// [day1]
+/// part1 = 56397
+/// part2 = "some string"
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers(BTreeMap<(u8, u8), Answer>);
+
+pub const DEFAULT_PATH: &str = "answers.toml";
+
+fn parse_value(s: &str) -> Option<Answer> {
+    if let Some(inner) = s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        return Some(Answer::Str(inner.to_string()));
+    }
+    let s = s.replace('_', "");
+    if let Ok(v) = s.parse::<i64>() {
+        return Some(Answer::Int(v));
+    }
+    s.parse::<u128>().ok().map(Answer::U128)
+}
+
+impl FromStr for Answers {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        let mut day = None;
+        for (i, line) in s.lines().enumerate() {
+            let err = |msg: &str| format!("line {}: {msg}: {line}", i + 1);
+            let line = line.split_once('#').map_or(line, |(l, _)| l).trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(section) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let d = section.strip_prefix("day").and_then(|d| d.parse().ok());
+                day = Some(d.ok_or_else(|| err("expected [dayN]"))?);
+                continue;
+            }
+            let day = day.ok_or_else(|| err("answer outside of a [dayN] section"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected key = value"))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(err("expected part1 or part2")),
+            };
+            let value = parse_value(value.trim()).ok_or_else(|| err("bad value"))?;
+            answers.0.insert((day, part), value);
+        }
+        Ok(answers)
+    }
+}
+
+impl Answers {
+    pub fn get(&self, day: u8, part: u8) -> Option<&Answer> {
+        self.0.get(&(day, part))
+    }
+
+    /// Compares each part in the result against the known answers.
+    pub fn check(&self, result: &DayResult) -> Vec<Check> {
+        result
+            .parts
+            .iter()
+            .map(|p| {
+                let status = match self.get(result.day, p.part) {
+                    Some(expected) if *expected == p.answer => CheckStatus::Pass,
+                    Some(expected) => CheckStatus::Fail(expected.clone()),
+                    None => CheckStatus::Missing,
+                };
+                Check {
+                    day: result.day,
+                    part: p.part,
+                    answer: p.answer.clone(),
+                    status,
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    // With the expected answer.
+    Fail(Answer),
+    // No known answer to compare against.
+    Missing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Check {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub status: CheckStatus,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {} part {}: ", self.day, self.part)?;
+        match &self.status {
+            CheckStatus::Pass => write!(f, "pass"),
+            CheckStatus::Fail(expected) => {
+                write!(f, "FAIL (got {}, expected {expected})", self.answer)
+            }
+            CheckStatus::Missing => write!(f, "missing (got {})", self.answer),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::runner::PartResult;
+
+    #[test]
+    fn test_answers() {
+        let answers: Answers = "
+# comment
+[day1]
+part1 = 1_234
+part2 = \"abc\" # trailing comment
+
+[day25]
+part1 = 340282366920938463463374607431768211455
+"
+        .parse()
+        .unwrap();
+        assert_eq!(answers.get(1, 1), Some(&Answer::Int(1234)));
+        assert_eq!(answers.get(1, 2), Some(&Answer::Str("abc".to_string())));
+        assert_eq!(answers.get(25, 1), Some(&Answer::U128(u128::MAX)));
+        assert_eq!(answers.get(25, 2), None);
+
+        assert!("part1 = 1".parse::<Answers>().is_err());
+        assert!("[day1]\npart3 = 1".parse::<Answers>().is_err());
+        assert!("[day1]\npart1 = x".parse::<Answers>().is_err());
+
+        let part = |part, answer: i64| PartResult {
+            part,
+            answer: answer.into(),
+            time: Duration::ZERO,
+        };
+        let result = DayResult {
+            day: 1,
+            parse_time: Duration::ZERO,
+            parts: vec![part(1, 1234), part(2, 5)],
+        };
+        let checks = answers.check(&result);
+        assert_eq!(checks[0].status, CheckStatus::Pass);
+        assert_eq!(
+            checks[1].status,
+            CheckStatus::Fail(Answer::Str("abc".to_string()))
+        );
+        assert_eq!(
+            checks[1].to_string(),
+            "day 1 part 2: FAIL (got 5, expected abc)"
+        );
+        let result = DayResult { day: 2, ..result };
+        assert_eq!(answers.check(&result)[0].status, CheckStatus::Missing);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{collections::BTreeMap, fmt, str::FromStr, time::Duration};
+
+use crate::{runner::Runnable, ParseError};
+
+/// Timing summary over several runs of one phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty());
+        samples.sort();
+        Self {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// Phase names are "parse", "part1" and "part2".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DayBench {
+    pub day: u8,
+    pub phases: Vec<(String, Stats)>,
+}
+
+/// Runs given day `runs` times, collecting timings of each phase.
+pub fn bench(
+    solution: &dyn Runnable,
+    day: u8,
+    input: &str,
+    parts: &[u8],
+    runs: usize,
+) -> Result<DayBench, ParseError> {
+    let mut samples: BTreeMap<String, Vec<Duration>> = BTreeMap::new();
+    for _ in 0..runs {
+        let result = solution.run(day, input, parts)?;
+        samples
+            .entry("parse".to_string())
+            .or_default()
+            .push(result.parse_time);
+        for part in result.parts {
+            samples
+                .entry(format!("part{}", part.part))
+                .or_default()
+                .push(part.time);
+        }
+    }
+    let phases = samples
+        .into_iter()
+        .map(|(phase, s)| (phase, Stats::from_samples(s)))
+        .collect();
+    Ok(DayBench { day, phases })
+}
+
+/// Saved results to compare later runs against.
+///
+/// Stored as tab separated lines of day, phase, and min/median/max in nanoseconds.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Baseline(BTreeMap<(u8, String), Stats>);
+
+impl Baseline {
+    pub fn add(&mut self, bench: &DayBench) {
+        for (phase, stats) in &bench.phases {
+            self.0.insert((bench.day, phase.clone()), *stats);
+        }
+    }
+
+    pub fn get(&self, day: u8, phase: &str) -> Option<&Stats> {
+        self.0.get(&(day, phase.to_string()))
+    }
+}
+
+impl FromStr for Baseline {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut baseline = Baseline::default();
+        for (i, line) in s.lines().enumerate() {
+            let items: Vec<_> = line.split('\t').collect();
+            let [day, phase, min, median, max] = items[..] else {
+                return Err(format!("line {}: expected 5 fields: {line}", i + 1));
+            };
+            let day = day
+                .parse()
+                .map_err(|_| format!("line {}: bad day", i + 1))?;
+            let nanos = |s: &str| {
+                s.parse()
+                    .map(Duration::from_nanos)
+                    .map_err(|_| format!("line {}: bad duration: {s}", i + 1))
+            };
+            let stats = Stats {
+                min: nanos(min)?,
+                median: nanos(median)?,
+                max: nanos(max)?,
+            };
+            baseline.0.insert((day, phase.to_string()), stats);
+        }
+        Ok(baseline)
+    }
+}
+
+impl fmt::Display for Baseline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for ((day, phase), stats) in &self.0 {
+            writeln!(
+                f,
+                "{day}\t{phase}\t{}\t{}\t{}",
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.max.as_nanos()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats one phase, with change of median against the baseline if there's one.
+pub fn format_phase(day: u8, phase: &str, stats: &Stats, baseline: Option<&Stats>) -> String {
+    let mut s = format!(
+        "day {day} {phase}: min {:?} median {:?} max {:?}",
+        stats.min, stats.median, stats.max
+    );
+    match baseline {
+        // Too fast to measure, so there is nothing to compare against.
+        Some(base) if base.median.is_zero() => {
+            s.push_str(&format!(" (baseline median {:?})", base.median));
+        }
+        Some(base) => {
+            let change = (stats.median.as_secs_f64() / base.median.as_secs_f64() - 1.0) * 100.0;
+            s.push_str(&format!(
+                " (baseline median {:?}, {change:+.1}%)",
+                base.median
+            ));
+        }
+        None => {}
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_and_baseline() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(vec![ms(5), ms(1), ms(3), ms(2), ms(4)]);
+        assert_eq!(
+            stats,
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                max: ms(5)
+            }
+        );
+
+        let mut baseline = Baseline::default();
+        baseline.add(&DayBench {
+            day: 7,
+            phases: vec![("part1".to_string(), stats)],
+        });
+        let saved = baseline.to_string();
+        assert_eq!(saved, "7\tpart1\t1000000\t3000000\t5000000\n");
+        assert_eq!(saved.parse::<Baseline>(), Ok(baseline.clone()));
+        assert!("7\tpart1\t1".parse::<Baseline>().is_err());
+
+        let faster = Stats::from_samples(vec![ms(2)]);
+        assert_eq!(
+            format_phase(7, "part1", &faster, baseline.get(7, "part1")),
+            "day 7 part1: min 2ms median 2ms max 2ms (baseline median 3ms, -33.3%)"
+        );
+        let zero = Stats::from_samples(vec![Duration::ZERO]);
+        assert_eq!(
+            format_phase(7, "part2", &faster, Some(&zero)),
+            "day 7 part2: min 2ms median 2ms max 2ms (baseline median 0ns)"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::{fmt, str::FromStr};
+
+use crate::{split_once, ParseError, Point, Pos3};
+
+/// Axis-aligned box of whole cells, both corners included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub min: Pos3,
+    pub max: Pos3,
+}
+
+impl Cuboid {
+    /// Box spanning two opposite corners, in any order.
+    pub fn new(a: Pos3, b: Pos3) -> Self {
+        let (min, max) = Pos3::bounding_box(&[a, b]).unwrap();
+        Self { min, max }
+    }
+
+    pub fn contains(&self, pos: &Pos3) -> bool {
+        pos.in_box(&self.min, &self.max)
+    }
+
+    pub fn intersects(&self, other: &Cuboid) -> bool {
+        (0..3).all(|i| self.min[i] <= other.max[i] && other.min[i] <= self.max[i])
+    }
+
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        self.intersects(other).then(|| Cuboid {
+            min: Point(std::array::from_fn(|i| self.min[i].max(other.min[i]))),
+            max: Point(std::array::from_fn(|i| self.max[i].min(other.max[i]))),
+        })
+    }
+
+    pub fn translate(&self, offset: Pos3) -> Cuboid {
+        Cuboid {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// Number of cells.
+    pub fn volume(&self) -> u64 {
+        (0..3)
+            .map(|i| (self.max[i] - self.min[i] + 1) as u64)
+            .product()
+    }
+
+    /// All cells, x changing fastest and z slowest.
+    pub fn cells(&self) -> impl Iterator<Item = Pos3> {
+        Pos3::box_points(self.min, self.max)
+    }
+
+    /// Corners of the shadow on the plane across `axis`, keeping the other two
+    /// coordinates in order, e.g. (x, y) for the z axis.
+    pub fn project(&self, axis: usize) -> (Point<2>, Point<2>) {
+        assert!(axis < 3, "no axis {axis}");
+        let drop = |p: Pos3| (0..3).filter(|&i| i != axis).map(|i| p[i]).collect();
+        (drop(self.min), drop(self.max))
+    }
+}
+
+impl FromStr for Cuboid {
+    type Err = ParseError;
+
+    /// Parses two corners like "1,0,1~1,2,1".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (a, b) = split_once(s, "~")?;
+        Ok(Cuboid::new(a.parse()?, b.parse()?))
+    }
+}
+
+impl fmt::Display for Cuboid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [x1, y1, z1] = self.min.0;
+        let [x2, y2, z2] = self.max.0;
+        write!(f, "{x1},{y1},{z1}~{x2},{y2},{z2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cuboid() {
+        let a: Cuboid = "2,2,2~0,0,0".parse().unwrap();
+        assert_eq!(a.min, Pos3::new([0, 0, 0]));
+        assert_eq!(a.to_string(), "0,0,0~2,2,2");
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.cells().count(), 27);
+        assert!(a.contains(&Pos3::new([1, 2, 0])));
+        assert!(!a.contains(&Pos3::new([1, 3, 0])));
+
+        let b = a.translate(Pos3::new([2, 1, -2]));
+        assert_eq!(b.to_string(), "2,1,-2~4,3,0");
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b).unwrap().to_string(), "2,1,0~2,2,0");
+        let c = b.translate(Pos3::new([1, 0, 0]));
+        assert!(!a.intersects(&c));
+        assert_eq!(a.intersection(&c), None);
+
+        assert_eq!(b.project(2), (Point([2, 1]), Point([4, 3])));
+        assert_eq!(b.project(0), (Point([1, -2]), Point([3, 0])));
+        assert!("0,0~1,1".parse::<Cuboid>().is_err());
+        assert!("0,0,0-1,1,1".parse::<Cuboid>().is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// States of an iterated function up to where they start repeating.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<S> {
+    // Steps before the first state that is part of the cycle.
+    pub start: usize,
+    pub len: usize,
+    // One state for each of the first `start + len` steps.
+    states: Vec<S>,
+}
+
+impl<S> Cycle<S> {
+    /// Earliest step with the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+
+    /// State after `n` steps, for any `n`.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.reduce(n)]
+    }
+
+    /// States making up the cycle, in order.
+    pub fn cycle(&self) -> &[S] {
+        &self.states[self.start..]
+    }
+}
+
+/// Finds the cycle by remembering every state, keyed by `key`. States with
+/// the same key are treated as equal.
+pub fn detect_by_key<S, K: Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle<S> {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+    loop {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(e) => {
+                let start = *e.get();
+                return Cycle {
+                    start,
+                    len: states.len() - start,
+                    states,
+                };
+            }
+            Entry::Vacant(e) => {
+                e.insert(states.len());
+            }
+        }
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Finds the cycle by remembering every state.
+pub fn detect<S: Clone + Hash + Eq>(initial: S, step: impl FnMut(&S) -> S) -> Cycle<S> {
+    detect_by_key(initial, step, S::clone)
+}
+
+/// Finds the cycle with Brent's algorithm, which only needs to compare
+/// states and never hashes them.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle<S> {
+    // The hare runs ahead in stretches that double in length, with the
+    // tortoise waiting at the start of each, until the hare comes around.
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    // Two walkers `len` steps apart first meet where the cycle starts.
+    let mut states = vec![initial];
+    while states.len() <= len {
+        states.push(step(states.last().unwrap()));
+    }
+    let mut start = 0;
+    while states[start] != states[start + len] {
+        states.push(step(states.last().unwrap()));
+        start += 1;
+    }
+    states.truncate(start + len);
+    Cycle { start, len, states }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle() {
+        // 3, 9, 27 % 20 = 7, 21 % 20 = 1, 3, ...: no prefix and a cycle of 4.
+        let times3 = |n: &u32| n * 3 % 20;
+        let found = detect(3, times3);
+        assert_eq!((found.start, found.len), (0, 4));
+        assert_eq!(found.cycle(), &[3, 9, 7, 1]);
+        assert_eq!(brent(3, times3), found);
+
+        // 2, 5, 3, 10, 9, 13, 9, ...: four steps before a cycle of 2.
+        let square_plus1 = |n: &u32| (n * n + 1) % 23;
+        let found = brent(2, square_plus1);
+        assert_eq!((found.start, found.len), (4, 2));
+        assert_eq!(*found.state_at(3), 10);
+        assert_eq!(*found.state_at(7), 13);
+        assert_eq!(found.reduce(1_000_000_001), 5);
+        assert_eq!(*found.state_at(1_000_000_000), 9);
+        assert_eq!(detect(2, square_plus1), found);
+
+        // Keyed on the last digit only, 10 and 20 look the same.
+        let found = detect_by_key(10, |n| n + 10, |n| n % 10);
+        assert_eq!((found.start, found.len), (0, 1));
+
+        // A fixed point is a cycle of one.
+        let found = brent(5, |n| *n);
+        assert_eq!((found.start, found.len), (0, 1));
+    }
+}
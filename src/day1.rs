@@ -1,17 +1,19 @@
+use crate::{Answer, ParseError, Solution};
+
 const DIGITS: &[(&str, u32)] = &[
-    (&"one", 1),
-    (&"two", 2),
-    (&"three", 3),
-    (&"four", 4),
-    (&"five", 5),
-    (&"six", 6),
-    (&"seven", 7),
-    (&"eight", 8),
-    (&"nine", 9),
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
 ];
 
 fn try_parse_digit(s: &str) -> Option<u32> {
-    if let Some(digit) = s.chars().next().unwrap().to_digit(10) {
+    if let Some(digit) = s.chars().next()?.to_digit(10) {
         return Some(digit);
     }
     for (word, value) in DIGITS {
@@ -22,33 +24,67 @@ fn try_parse_digit(s: &str) -> Option<u32> {
     None
 }
 
-fn find_first_digit(s: &str) -> u32 {
-    (0..s.len()).find_map(|i| try_parse_digit(&s[i..])).unwrap()
+fn find_first_digit(s: &str) -> Option<u32> {
+    (0..s.len()).find_map(|i| try_parse_digit(s.get(i..)?))
 }
 
-fn find_last_digit(s: &str) -> u32 {
+fn find_last_digit(s: &str) -> Option<u32> {
     (0..s.len())
         .rev()
-        .find_map(|i| try_parse_digit(&s[i..]))
-        .unwrap()
+        .find_map(|i| try_parse_digit(s.get(i..)?))
+}
+
+fn parse_line_part1(line: &str) -> Option<u32> {
+    let first = line.chars().find_map(|c| c.to_digit(10))?;
+    let last = line.chars().rev().find_map(|c| c.to_digit(10))?;
+    Some(first * 10 + last)
 }
 
-fn parse_line_part1(line: &str) -> u32 {
-    let first = line.chars().find(|c| c.is_digit(10)).unwrap();
-    let last = line.chars().rev().find(|c| c.is_digit(10)).unwrap();
-    first.to_digit(10).unwrap() * 10 + last.to_digit(10).unwrap()
+fn parse_line_part2(line: &str) -> Option<u32> {
+    let first = find_first_digit(line)?;
+    let last = find_last_digit(line)?;
+    Some(first * 10 + last)
 }
 
-fn parse_line_part2(line: &str) -> u32 {
-    let first = find_first_digit(line);
-    let last = find_last_digit(line);
-    first * 10 + last
+/// Calibration value of a line, read both ways.
+#[derive(Debug)]
+pub struct Calibration {
+    // None if the line only has spelled out digits.
+    digits: Option<u32>,
+    spelled: u32,
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let part1: u32 = input.lines().map(|line| parse_line_part1(line)).sum();
-    dbg!(&part1);
-    let part2: u32 = input.lines().map(|line| parse_line_part2(line)).sum();
-    dbg!(&part2);
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<Calibration>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let values = input
+            .lines()
+            .map(|line| {
+                let spelled = parse_line_part2(line)
+                    .ok_or_else(|| ParseError::new(line, "line with a digit"))?;
+                Ok(Calibration {
+                    digits: parse_line_part1(line),
+                    spelled,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if values.is_empty() {
+            return Err(ParseError::at_end(input, "calibration lines"));
+        }
+        Ok(values)
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        // Lines without a plain digit are only valid in part 2.
+        let part1: Option<u32> = input.iter().map(|c| c.digits).sum();
+        part1.map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        let part2: u32 = input.iter().map(|c| c.spelled).sum();
+        part2.into()
+    }
 }
@@ -1,58 +1,32 @@
-use adv2023::Pos;
-use std::collections::VecDeque;
-use std::{iter::repeat, str::FromStr};
+use crate::{search, Answer, Grid, ParseError, Pos, Solution};
+use std::str::FromStr;
 
-#[derive(Debug)]
-struct Map {
-    tiles: Vec<Vec<char>>,
-    distance: Vec<Vec<isize>>,
+#[derive(Debug, Clone)]
+pub struct Map {
+    tiles: Grid<char>,
+    distance: Grid<isize>,
     start: Pos,
-    size: Pos,
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let tiles: Vec<Vec<char>> = s.lines().map(|line| line.chars().collect()).collect();
-        let size = Pos::new(tiles.len() as isize, tiles[0].len() as isize);
-        let mut distance = Vec::new();
-        let mut start = None;
-        for y in 0..size.y {
-            distance.push(Vec::from_iter(repeat(-1).take(tiles[0].len())));
-            for x in 0..size.x {
-                if tiles[y as usize][x as usize] == 'S' {
-                    start = Some(Pos::new(y as isize, x as isize));
-                }
-            }
-        }
+        let tiles: Grid<char> = s.parse()?;
+        let start = tiles
+            .find(&'S')
+            .ok_or_else(|| ParseError::new(s, "start tile 'S'"))?;
         Ok(Self {
+            distance: Grid::new(tiles.size(), -1),
             tiles,
-            distance,
-            start: start.unwrap(),
-            size,
+            start,
         })
     }
 }
 
 impl Map {
-    fn t(&self, pos: Pos) -> char {
-        self.tiles[pos.y as usize][pos.x as usize]
-    }
-
-    fn d(&self, pos: Pos) -> isize {
-        self.distance[pos.y as usize][pos.x as usize]
-    }
-
-    fn in_bounds(&self, pos: Pos) -> bool {
-        pos.x >= 0 && pos.x < self.size.x && pos.y >= 0 && pos.y < self.size.y
-    }
-
     fn connects(&self, pos: Pos) -> Option<(Pos, Pos)> {
-        if !self.in_bounds(pos) {
-            return None;
-        }
-        match self.t(pos) {
+        match self.tiles.get(pos)? {
             '|' => Some((pos + Pos::up(), pos + Pos::down())),
             '-' => Some((pos + Pos::left(), pos + Pos::right())),
             'L' => Some((pos + Pos::up(), pos + Pos::right())),
@@ -63,20 +37,12 @@ impl Map {
         }
     }
 
-    fn clear_distance(&mut self) {
-        for y in 0..self.size.y {
-            for x in 0..self.size.x {
-                self.distance[y as usize][x as usize] = -1;
-            }
-        }
-    }
-
     fn find_path(&mut self) -> isize {
         // Try out all possible options for 'S'.
         for s in "|-LJ7F".chars() {
             // dbg!(&s);
-            self.tiles[self.start.y as usize][self.start.x as usize] = s;
-            self.clear_distance();
+            self.tiles[self.start] = s;
+            self.distance = Grid::new(self.tiles.size(), -1);
             if let Some(dist) = self.explore(self.start) {
                 return dist;
             }
@@ -84,44 +50,36 @@ impl Map {
         panic!("no way");
     }
 
-    fn have_visited(&self, pos: Pos) -> bool {
-        self.d(pos) != -1
-    }
-
-    fn explore(&mut self, pos: Pos) -> Option<isize> {
-        let mut todo = VecDeque::<(Pos, isize)>::new();
-        todo.push_back((pos, 0));
-
-        while let Some((pos, dist)) = todo.pop_front() {
-            // println!("pos={pos:?} dist={dist}");
-            if self.d(pos) == dist {
-                return Some(dist);
-            }
-            self.distance[pos.y as usize][pos.x as usize] = dist;
-            if let Some((to1, to2)) = self.connects(pos) {
-                if !self.in_bounds(to1) || !self.in_bounds(to2) {
-                    return None;
-                }
-                if self.d(to1) != (dist - 1) && self.d(to2) != (dist - 1) && dist != 0 {
-                    // We didn't come from either of the directions.
-                    return None;
-                }
-                if !self.have_visited(to1) {
-                    todo.push_back((to1, dist + 1));
-                }
-                if !self.have_visited(to2) {
-                    todo.push_back((to2, dist + 1));
-                }
-            } else {
-                return None;
-            }
+    // Walks the pipes from `start`, returning the distance to the farthest
+    // tile if they form a closed loop.
+    fn explore(&mut self, start: Pos) -> Option<isize> {
+        let distances = search::bfs([start], |&pos| {
+            self.connects(pos)
+                .map(|(to1, to2)| [to1, to2])
+                .into_iter()
+                .flatten()
+        });
+        // Each tile must connect back to both tiles it connects to.
+        let connects_back = |from: Pos, to: Pos| {
+            self.connects(to)
+                .is_some_and(|(to1, to2)| to1 == from || to2 == from)
+        };
+        let closed = distances.keys().all(|&pos| match self.connects(pos) {
+            Some((to1, to2)) => connects_back(pos, to1) && connects_back(pos, to2),
+            None => false,
+        });
+        if !closed {
+            return None;
         }
-        None
+        for (&pos, &dist) in &distances {
+            self.distance[pos] = dist as isize;
+        }
+        distances.values().max().map(|&dist| dist as isize)
     }
 
     fn count_inside(&self) -> isize {
         let mut count = 0;
-        for y in 0..self.size.y {
+        for y in 0..self.tiles.height() {
             // println!(
             //     "y={y} | {} | {}",
             //     join(&self.tiles[y as usize], ""),
@@ -130,9 +88,10 @@ impl Map {
             // dbg!(&self.distance[y as usize]);
             let mut inside = false;
             let mut wall_enter = ' ';
-            for x in 0..self.size.x {
-                if self.distance[y as usize][x as usize] != -1 {
-                    let t = self.tiles[y as usize][x as usize];
+            for x in 0..self.tiles.width() {
+                let pos = Pos::new(y, x);
+                if self.distance[pos] != -1 {
+                    let t = self.tiles[pos];
                     match t {
                         '|' => inside = !inside,
                         'F' | 'L' => {
@@ -155,12 +114,23 @@ impl Map {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut map: Map = input.parse().unwrap();
-    // Part 1:
-    dbg!(map.find_path());
-    // dbg!(&map);
-    // Part 2:
-    dbg!(map.count_inside());
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> Answer {
+        map.clone().find_path().into()
+    }
+
+    fn part2(map: &Self::Input) -> Answer {
+        // Counting needs the loop (and the real 'S' tile) found in part 1.
+        let mut map = map.clone();
+        map.find_path();
+        map.count_inside().into()
+    }
 }
@@ -1,20 +1,25 @@
 use std::{collections::HashSet, str::FromStr};
 
-use adv2023::Pos;
+use log::trace;
+
+use crate::{Answer, AsPoint, ParseError, Pos, Solution};
 
 #[derive(Debug)]
-struct Image {
+pub struct Image {
     galaxies: Vec<Pos>,
     empty_rows: HashSet<isize>,
     empty_cols: HashSet<isize>,
 }
 
 impl FromStr for Image {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut galaxies = vec![];
         let lines: Vec<&str> = s.lines().collect();
+        if lines.is_empty() {
+            return Err(ParseError::at_end(s, "image rows"));
+        }
         let mut empty_rows = HashSet::from_iter(0..(lines.len() as isize));
         let mut empty_cols = HashSet::from_iter(0..(lines[0].len() as isize));
         lines.iter().enumerate().for_each(|(y, line)| {
@@ -41,16 +46,8 @@ impl Image {
             let g1 = self.galaxies[i];
             for j in (i + 1)..self.galaxies.len() {
                 let g2 = self.galaxies[j];
-                let (y_min, y_max) = if g1.y < g2.y {
-                    (g1.y, g2.y)
-                } else {
-                    (g2.y, g1.y)
-                };
-                let (x_min, x_max) = if g1.x < g2.x {
-                    (g1.x, g2.x)
-                } else {
-                    (g2.x, g1.x)
-                };
+                let (y_min, y_max) = (g1.y.min(g2.y), g1.y.max(g2.y));
+                let (x_min, x_max) = (g1.x.min(g2.x), g1.x.max(g2.x));
 
                 // This could be more efficient, but meh.
                 let y_gaps = self
@@ -64,7 +61,7 @@ impl Image {
                     .filter(|&&x| x > x_min && x < x_max)
                     .count() as isize;
 
-                let path = (y_max - y_min) + (x_max - x_min) + (gap_size - 1) * (y_gaps + x_gaps);
+                let path = g1.manhattan(&g2) + (gap_size - 1) * (y_gaps + x_gaps);
                 sum += path;
             }
         }
@@ -72,10 +69,22 @@ impl Image {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let image: Image = input.parse().unwrap();
-    dbg!(&image);
-    dbg!(image.pairs_shortest_paths(1));
-    dbg!(image.pairs_shortest_paths(1_000_000));
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Image;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let image: Image = input.parse()?;
+        trace!("{image:?}");
+        Ok(image)
+    }
+
+    fn part1(image: &Self::Input) -> Answer {
+        image.pairs_shortest_paths(2).into()
+    }
+
+    fn part2(image: &Self::Input) -> Answer {
+        image.pairs_shortest_paths(1_000_000).into()
+    }
 }
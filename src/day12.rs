@@ -2,19 +2,27 @@ use std::{collections::HashMap, str::FromStr};
 
 use itertools::{interleave, repeat_n};
 
+use crate::{parse_num, split_once, Answer, ParseError, Solution};
+
 #[derive(Debug, Default)]
-struct Record {
+pub struct Record {
     cond: Vec<char>,
     groups: Vec<usize>,
 }
 
 impl FromStr for Record {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (cond_s, groups_s) = s.split_once(' ').unwrap();
+        let (cond_s, groups_s) = split_once(s, " ")?;
+        if let Some(pos) = cond_s.find(|c| !".#?".contains(c)) {
+            return Err(ParseError::new(&cond_s[pos..pos + 1], "'.', '#' or '?'"));
+        }
         let cond = cond_s.chars().collect();
-        let groups = groups_s.split(',').map(|x| x.parse().unwrap()).collect();
+        let groups = groups_s
+            .split(',')
+            .map(parse_num)
+            .collect::<Result<_, _>>()?;
         Ok(Self { cond, groups })
     }
 }
@@ -41,7 +49,7 @@ type Paths = HashMap<Path, usize>;
 impl Record {
     fn count_ways(&self) -> usize {
         let mut paths = Paths::from([(Path::default(), 1)]);
-        for (_i, &c) in self.cond.iter().enumerate() {
+        for &c in self.cond.iter() {
             let mut new_paths = Paths::new();
             for (path, path_count) in &paths {
                 if c == '.' || c == '?' {
@@ -94,13 +102,23 @@ impl Record {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let records: Vec<Record> = input.lines().map(|line| line.parse().unwrap()).collect();
-    let part1: usize = records.iter().map(|r| r.count_ways()).sum();
-    dbg!(part1);
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<Record>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(|line| line.parse()).collect()
+    }
+
+    fn part1(records: &Self::Input) -> Answer {
+        let part1: usize = records.iter().map(|r| r.count_ways()).sum();
+        part1.into()
+    }
 
-    let records_part2: Vec<Record> = records.iter().map(|r| r.make_into_part2()).collect();
-    let part2: usize = records_part2.iter().map(|r| r.count_ways()).sum();
-    dbg!(part2);
+    fn part2(records: &Self::Input) -> Answer {
+        let records_part2: Vec<Record> = records.iter().map(|r| r.make_into_part2()).collect();
+        let part2: usize = records_part2.iter().map(|r| r.count_ways()).sum();
+        part2.into()
+    }
 }
@@ -1,25 +1,31 @@
-type Map = Vec<Vec<char>>;
+use log::debug;
 
-fn parse_map(s: &str) -> Map {
-    s.lines().map(|line| line.chars().collect()).collect()
+use crate::{Answer, Grid, ParseError, Solution};
+
+type Map = Grid<char>;
+
+fn parse_map(s: &str) -> Result<Map, ParseError> {
+    let map = Grid::parse(s, |c| "#.".contains(c).then_some(c), "'#' or '.'")?;
+    if map.width() == 0 {
+        return Err(ParseError::at_end(s, "pattern of '#' and '.'"));
+    }
+    Ok(map)
 }
 
 fn check_vertical_mirror(map: &Map, col: usize) -> bool {
     for c in 0..=col {
-        if (col + 1 + c) >= map[0].len() {
+        if (col + 1 + c) >= map.width() {
             break;
         }
-        for r in 0..map.len() {
-            if map[r][col - c] != map[r][col + 1 + c] {
-                return false;
-            }
+        if !map.column(col - c).eq(map.column(col + 1 + c)) {
+            return false;
         }
     }
-    return true;
+    true
 }
 
 fn find_vertical_line(map: &Map, ignore_col: Option<usize>) -> Option<usize> {
-    for col in 0..(map[0].len() - 1) {
+    for col in 0..(map.width() - 1) {
         if Some(col) == ignore_col {
             continue;
         }
@@ -32,20 +38,18 @@ fn find_vertical_line(map: &Map, ignore_col: Option<usize>) -> Option<usize> {
 
 fn check_horizontal_mirror(map: &Map, row: usize) -> bool {
     for r in 0..=row {
-        if (row + 1 + r) >= map.len() {
+        if (row + 1 + r) >= map.height() {
             break;
         }
-        for c in 0..map[0].len() {
-            if map[row - r][c] != map[row + 1 + r][c] {
-                return false;
-            }
+        if map.row(row - r) != map.row(row + 1 + r) {
+            return false;
         }
     }
-    return true;
+    true
 }
 
 fn find_horizontal_line(map: &Map, ignore_row: Option<usize>) -> Option<usize> {
-    for row in 0..(map.len() - 1) {
+    for row in 0..(map.height() - 1) {
         if Some(row) == ignore_row {
             continue;
         }
@@ -75,28 +79,38 @@ fn flip(c: char) -> char {
 }
 
 fn part2(map: &mut Map) -> usize {
-    let ignore_col = dbg!(find_vertical_line(map, None));
-    let ignore_row = dbg!(find_horizontal_line(map, None));
-    for r in 0..map.len() {
-        for c in 0..map[0].len() {
-            map[r][c] = flip(map[r][c]);
-            if let Some(col) = find_vertical_line(map, ignore_col) {
-                return col + 1;
-            }
-            if let Some(row) = find_horizontal_line(map, ignore_row) {
-                return (row + 1) * 100;
-            }
-            map[r][c] = flip(map[r][c]);
+    let ignore_col = find_vertical_line(map, None);
+    let ignore_row = find_horizontal_line(map, None);
+    debug!("original mirror: col {ignore_col:?} row {ignore_row:?}");
+    for pos in map.positions() {
+        map[pos] = flip(map[pos]);
+        if let Some(col) = find_vertical_line(map, ignore_col) {
+            return col + 1;
         }
+        if let Some(row) = find_horizontal_line(map, ignore_row) {
+            return (row + 1) * 100;
+        }
+        map[pos] = flip(map[pos]);
     }
     panic!()
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut maps: Vec<Map> = input.split("\n\n").map(|s| parse_map(s)).collect();
-    let part1: usize = maps.iter().map(part1).sum();
-    dbg!(&part1);
-    let part2: usize = maps.iter_mut().map(part2).sum();
-    dbg!(&part2);
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Vec<Map>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.split("\n\n").map(parse_map).collect()
+    }
+
+    fn part1(maps: &Self::Input) -> Answer {
+        let part1: usize = maps.iter().map(part1).sum();
+        part1.into()
+    }
+
+    fn part2(maps: &Self::Input) -> Answer {
+        let part2: usize = maps.clone().iter_mut().map(part2).sum();
+        part2.into()
+    }
 }
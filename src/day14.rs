@@ -1,11 +1,8 @@
-use std::{
-    collections::{HashMap, HashSet},
-    hash::Hash,
-    hash::Hasher,
-    str::FromStr,
-};
+use std::{collections::HashMap, hash::Hash, hash::Hasher, str::FromStr};
 
-use adv2023::Pos;
+use log::debug;
+
+use crate::{cycle, Answer, Grid, ParseError, Pos, Solution};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
 enum RockType {
@@ -23,7 +20,7 @@ struct Rock {
 struct Rocks(Vec<Rock>);
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
-struct Map {
+pub struct Map {
     size: Pos,
     rocks: Rocks,
     // From position to index in 'rocks'.
@@ -38,8 +35,9 @@ impl Hash for Map {
 }
 
 impl Map {
+    #[allow(dead_code)]
     fn dump(&self) {
-        println!("=============");
+        eprintln!("=============");
         for y in 0..self.size.y {
             for x in 0..self.size.x {
                 let c = match self
@@ -51,24 +49,21 @@ impl Map {
                     Some(&RockType::Round) => 'O',
                     None => ' ',
                 };
-                print!("{}", c);
+                eprint!("{}", c);
             }
-            println!();
+            eprintln!();
         }
-        println!("=============");
-        println!();
+        eprintln!("=============");
+        eprintln!();
     }
     fn add_rock(&mut self, rock: Rock) {
-        assert!(self
-            .lookup
-            .insert(rock.pos.clone(), self.rocks.0.len())
-            .is_none());
+        assert!(self.lookup.insert(rock.pos, self.rocks.0.len()).is_none());
         self.rocks.0.push(rock);
     }
 
     fn move_rock(&mut self, from: Pos, to: Pos) {
         let idx = self.lookup.remove(&from).unwrap();
-        self.rocks.0[idx].pos = to.clone();
+        self.rocks.0[idx].pos = to;
         assert!(self.lookup.insert(to, idx).is_none());
     }
 
@@ -105,7 +100,7 @@ impl Map {
                 }
                 loop {
                     let to = pos + *dir;
-                    if to.y < 0 || to.y >= self.size.y || to.x < 0 || to.x >= self.size.x {
+                    if !to.check_bounds(&self.size) {
                         break;
                     }
                     if self.lookup.contains_key(&to) {
@@ -146,34 +141,37 @@ impl Map {
             .0
             .iter()
             .filter(|r| r.rock_type == RockType::Round)
-            .map(|r| self.size.y as isize - r.pos.y as isize)
+            .map(|r| self.size.y - r.pos.y)
             .sum()
     }
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let lines: Vec<&str> = s.lines().collect();
-        let size = Pos::new(lines.len(), lines[0].len());
+        let grid = Grid::parse(
+            s,
+            |c| match c {
+                'O' => Some(Some(RockType::Round)),
+                '#' => Some(Some(RockType::Cube)),
+                '.' => Some(None),
+                _ => None,
+            },
+            "'O', '#' or '.'",
+        )?;
         let mut map = Map {
-            size,
+            size: grid.size(),
             ..Default::default()
         };
-        lines.iter().enumerate().for_each(|(y, line)| {
-            line.chars().enumerate().for_each(|(x, c)| {
-                let pos = Pos::new(y, x);
-                let rock_type = if c == 'O' {
-                    RockType::Round
-                } else if c == '#' {
-                    RockType::Cube
-                } else {
-                    return;
-                };
-                map.add_rock(Rock { pos, rock_type });
-            });
-        });
+        for (pos, rock_type) in grid.iter() {
+            if let Some(rock_type) = rock_type {
+                map.add_rock(Rock {
+                    pos,
+                    rock_type: rock_type.clone(),
+                });
+            }
+        }
         Ok(map)
     }
 }
@@ -184,40 +182,44 @@ fn part1(map: &Map) -> isize {
     map.calculate_load()
 }
 
-fn cycle(map: &mut Map) {
+fn spin_cycle(map: &Map) -> Map {
+    let mut map = map.clone();
     map.roll(&Pos::up());
     map.roll(&Pos::left());
     map.roll(&Pos::down());
     map.roll(&Pos::right());
+    map
 }
 
 fn part2(map: &Map) -> isize {
-    let mut map = map.clone();
-    let mut states_seen = HashMap::<Rocks, usize>::new();
-
-    for i in 0..1000000 {
-        dbg!(i);
+    // Rocks of a kind are interchangeable, so compare where they are only.
+    let found = cycle::detect_by_key(map.clone(), spin_cycle, |map| {
         let mut rocks = map.rocks.clone();
         rocks.0.sort();
-        if let Some(prev_i) = states_seen.get(&rocks) {
-            let delta = i - prev_i;
-            let rem = (1_000_000_000 - i) % delta;
-            for _ in 0..rem {
-                cycle(&mut map);
-            }
-            return map.calculate_load();
-        }
-        states_seen.insert(rocks, i);
-        cycle(&mut map);
-    }
-    todo!()
+        rocks
+    });
+    debug!(
+        "cycle found: step {} repeats step {}",
+        found.start + found.len,
+        found.start
+    );
+    found.state_at(1_000_000_000).calculate_load()
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let map: Map = input.parse().unwrap();
-    map.dump();
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> Answer {
+        part1(map).into()
+    }
 
-    dbg!(part1(&map));
-    dbg!(part2(&map));
+    fn part2(map: &Self::Input) -> Answer {
+        part2(map).into()
+    }
 }
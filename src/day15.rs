@@ -1,42 +1,84 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
+use log::trace;
+
+use crate::{Answer, ParseError, Solution};
+
+// Only called on ASCII strings, checked while parsing.
 fn hash(s: &str) -> u8 {
     let mut v = 0usize;
-    for c in s.chars() {
-        let c8: u8 = c.try_into().unwrap();
-        v += c8 as usize;
-        // v += c. as_ascii().unwrap().to_u8() as usize;
+    for c in s.bytes() {
+        v += c as usize;
         v *= 17;
         v %= 256;
     }
     v as u8
 }
 
-fn part2(s: &str) -> usize {
+#[derive(Debug)]
+pub enum Op {
+    Remove,
+    Insert(u8),
+}
+
+#[derive(Debug)]
+pub struct Step {
+    text: String,
+    label: String,
+    op: Op,
+}
+
+impl FromStr for Step {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !s.is_ascii() {
+            return Err(ParseError::new(s, "ASCII step"));
+        }
+        let (label, op) = if let Some(label) = s.strip_suffix('-') {
+            (label, Op::Remove)
+        } else {
+            let (label, lens_str) = s
+                .split_once('=')
+                .ok_or_else(|| ParseError::new(s, "\"label=lens\" or \"label-\""))?;
+            let lens = lens_str
+                .parse()
+                .map_err(|_| ParseError::new(lens_str, "focal length"))?;
+            (label, Op::Insert(lens))
+        };
+        Ok(Step {
+            text: s.to_string(),
+            label: label.to_string(),
+            op,
+        })
+    }
+}
+
+fn part2(steps: &[Step]) -> usize {
     let mut hashmap = HashMap::<u8, Vec<(String, u8)>>::new();
-    s.trim().split(',').for_each(|op| {
-        if op.ends_with('-') {
-            let label = op.trim_end_matches('-');
-            let h = hash(label);
-            if let Some(v) = hashmap.get_mut(&h) {
-                v.retain(|(s, _)| s != label);
+    steps.iter().for_each(|step| {
+        let label = &step.label;
+        let h = hash(label);
+        match step.op {
+            Op::Remove => {
+                if let Some(v) = hashmap.get_mut(&h) {
+                    v.retain(|(s, _)| s != label);
+                }
             }
-        } else {
-            let (label, lens_str) = op.split_once('=').unwrap();
-            let lens: u8 = lens_str.parse().unwrap();
-            let h = hash(label);
-            let v = hashmap.entry(h).or_insert(vec![]);
-            for (s, prev_lens) in v.iter_mut() {
-                if s == label {
-                    *prev_lens = lens;
-                    return;
+            Op::Insert(lens) => {
+                let v = hashmap.entry(h).or_insert(vec![]);
+                for (s, prev_lens) in v.iter_mut() {
+                    if s == label {
+                        *prev_lens = lens;
+                        return;
+                    }
                 }
+                v.push((label.to_string(), lens));
             }
-            v.push((label.to_string(), lens));
         }
     });
 
-    dbg!(&hashmap);
+    trace!("{hashmap:?}");
     let mut power = 0usize;
     for h in 0u8..=255 {
         if let Some(v) = hashmap.get(&h) {
@@ -48,13 +90,25 @@ fn part2(s: &str) -> usize {
     power
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let part1: usize = input
-        .trim()
-        .split(',')
-        .map(|x| dbg!(hash(x) as usize))
-        .sum();
-    dbg!(&part1);
-    dbg!(part2(&input));
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Step>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(ParseError::at_end(input, "comma separated steps"));
+        }
+        input.split(',').map(str::parse).collect()
+    }
+
+    fn part1(input: &Self::Input) -> Answer {
+        let part1: usize = input.iter().map(|step| hash(&step.text) as usize).sum();
+        part1.into()
+    }
+
+    fn part2(input: &Self::Input) -> Answer {
+        part2(input).into()
+    }
 }
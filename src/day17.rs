@@ -1,33 +1,25 @@
 #![allow(dead_code)]
 
-use std::{
-    cmp::min,
-    collections::{BTreeMap, HashMap},
-    str::FromStr,
-};
+use std::str::FromStr;
 
-use adv2023::Pos;
+use crate::{search, Answer, Direction, Grid, ParseError, Pos, Solution};
 
 #[derive(Debug)]
-struct Map {
-    blocks: Vec<Vec<u8>>,
+pub struct Map {
+    blocks: Grid<u8>,
     size: Pos,
     end: Pos,
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let blocks: Vec<Vec<u8>> = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| c.to_digit(10).unwrap() as u8)
-                    .collect()
-            })
-            .collect();
-        let size = Pos::new(blocks.len(), blocks[0].len());
+        let blocks = Grid::parse(s, |c| c.to_digit(10).map(|d| d as u8), "digit")?;
+        if blocks.width() == 0 {
+            return Err(ParseError::at_end(s, "rows of digits"));
+        }
+        let size = blocks.size();
         let end = size + Pos::new(-1, -1);
         Ok(Map { blocks, size, end })
     }
@@ -36,12 +28,12 @@ impl FromStr for Map {
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 struct State {
     pos: Pos,
-    dir: Pos,
+    dir: Direction,
     moves_this_dir: u8,
 }
 
 impl State {
-    fn go(&self, dir: Pos) -> Self {
+    fn go(&self, dir: Direction) -> Self {
         Self {
             pos: self.pos + dir,
             dir,
@@ -54,69 +46,32 @@ impl State {
     }
 }
 
-#[derive(Debug)]
-struct Search<'a> {
-    map: &'a Map,
-    best_heat_loss: HashMap<State, usize>,
-    to_visit: BTreeMap<State, usize>,
-}
-
-impl<'a> Search<'a> {
-    fn new(map: &'a Map) -> Search<'a> {
-        Self {
-            map,
-            best_heat_loss: HashMap::new(),
-            to_visit: BTreeMap::new(),
-        }
-    }
-
-    fn maybe_add_new_state(&mut self, state: State, heat_loss: usize) {
-        if !state.pos.check_bounds(&self.map.size) {
-            return;
-        }
-        let new_heat_loss =
-            heat_loss + self.map.blocks[state.pos.y as usize][state.pos.x as usize] as usize;
-        // Check if we already have visited the state with proposed or better heat loss.
-        if let Some(hl) = self.best_heat_loss.get(&state) {
-            if *hl <= new_heat_loss {
-                return;
-            }
-        }
-        // Check if we already have a pending visit, insert or update with best heat loss.
-        let hl_ref = self.to_visit.entry(state).or_insert(usize::MAX);
-        *hl_ref = min(*hl_ref, new_heat_loss);
-    }
-
-    fn search(&mut self, generate_moves: fn(&State) -> Vec<State>) -> usize {
-        self.best_heat_loss.clear();
-        self.to_visit.clear();
-        self.to_visit.insert(
-            State {
-                pos: Pos::new(0, 0),
-                dir: Pos::right(),
-                moves_this_dir: 0,
-            },
-            0,
-        );
-
-        while let Some((state, heat_loss)) = self.to_visit.pop_first() {
-            // println!("state: {:?} heat_loss: {}", &state, heat_loss);
-            let v = self
-                .best_heat_loss
-                .entry(state.clone())
-                .or_insert(usize::MAX);
-            *v = heat_loss;
-
-            for m in generate_moves(&state) {
-                self.maybe_add_new_state(m, heat_loss);
-            }
-        }
-        self.best_heat_loss
-            .iter()
-            .filter(|(k, _)| k.pos == self.map.end)
-            .map(|(_, v)| *v)
-            .min()
-            .unwrap()
+impl Map {
+    // `can_stop` says whether the crucible may come to a halt in a state.
+    // None if it can't stop at the end at all.
+    fn min_heat_loss(
+        &self,
+        generate_moves: fn(&State) -> Vec<State>,
+        can_stop: fn(&State) -> bool,
+    ) -> Option<usize> {
+        let start = State {
+            pos: Pos::new(0, 0),
+            dir: Direction::E,
+            moves_this_dir: 0,
+        };
+        let neighbors = |state: &State| {
+            generate_moves(state)
+                .into_iter()
+                .filter_map(|m| {
+                    let heat_loss = *self.blocks.get(m.pos)? as usize;
+                    Some((m, heat_loss))
+                })
+                .collect::<Vec<_>>()
+        };
+        let is_goal = |state: &State| state.pos == self.end && can_stop(state);
+        let result = search::dijkstra([start], neighbors, is_goal);
+        log::debug!("{:?}", result.stats);
+        result.path.map(|path| path.cost)
     }
 }
 
@@ -125,14 +80,13 @@ fn part1_generate_moves(state: &State) -> Vec<State> {
     if state.moves_this_dir < 3 {
         out.push(state.go(state.dir));
     }
-    out.push(state.go(state.dir.cw()));
-    out.push(state.go(state.dir.ccw()));
+    out.push(state.go(state.dir.turn_right()));
+    out.push(state.go(state.dir.turn_left()));
     out
 }
 
-fn part1(map: &Map) -> usize {
-    let mut search = Search::new(map);
-    search.search(part1_generate_moves)
+fn part1(map: &Map) -> Option<usize> {
+    map.min_heat_loss(part1_generate_moves, |_| true)
 }
 
 fn part2_generate_moves(state: &State) -> Vec<State> {
@@ -142,22 +96,49 @@ fn part2_generate_moves(state: &State) -> Vec<State> {
     }
     // moves_this_dir == 0 is special case for starting point where we can turn.
     if state.moves_this_dir == 0 || state.moves_this_dir >= 4 {
-        out.push(state.go(state.dir.cw()));
-        out.push(state.go(state.dir.ccw()));
+        out.push(state.go(state.dir.turn_right()));
+        out.push(state.go(state.dir.turn_left()));
     }
     out
 }
 
-fn part2(map: &Map) -> usize {
-    let mut search = Search::new(map);
-    search.search(part2_generate_moves)
+fn part2(map: &Map) -> Option<usize> {
+    // Ultra crucibles need 4 blocks in a row before they can stop, too.
+    map.min_heat_loss(part2_generate_moves, |state| state.moves_this_dir >= 4)
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let map: Map = input.parse().unwrap();
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Map;
 
-    // dbg!(&map);
-    // dbg!(part1(&map));
-    dbg!(part2(&map));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> Answer {
+        part1(map).map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(map: &Self::Input) -> Answer {
+        part2(map).map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part2_stop() {
+        let map: Map = "111111111111\n999999999991\n999999999991\n999999999991\n999999999991"
+            .parse()
+            .unwrap();
+        assert_eq!(part2(&map), Some(71));
+
+        // Too small for a run of 4 blocks.
+        let map: Map = "1234".parse().unwrap();
+        assert_eq!(part1(&map), Some(9));
+        assert_eq!(part2(&map), None);
+    }
 }
@@ -1,46 +1,27 @@
 use std::{collections::HashMap, str::FromStr};
 
-use itertools::Itertools;
+use crate::{parse_num, split_once, Answer, HyperRect, ParseError, Ranges, Solution};
 
-use adv2023::{Range, Ranges};
+type AcceptableRanges = HyperRect<char, isize>;
 
-#[derive(Clone, Debug)]
-struct AcceptableRanges(HashMap<char, Ranges>);
-
-impl AcceptableRanges {
-    fn all() -> Self {
-        Self(HashMap::from_iter([
-            ('x', Ranges::new(1, 4001)),
-            ('m', Ranges::new(1, 4001)),
-            ('a', Ranges::new(1, 4001)),
-            ('s', Ranges::new(1, 4001)),
-        ]))
-    }
-
-    fn combinations(&self) -> usize {
-        self.0.values().map(|r| r.len()).product1().unwrap()
-    }
+fn all_parts() -> AcceptableRanges {
+    "xmas".chars().map(|c| (c, Ranges::new(1, 4001))).collect()
+}
 
-    fn bisect(
-        &self,
-        xmas: char,
-        comparison: char,
-        against: isize,
-    ) -> (AcceptableRanges, AcceptableRanges) {
-        let (matching_range, other_range) = match comparison {
-            '<' => (Range::new(1, against), Range::new(against, 4001)),
-            '>' => (Range::new(against + 1, 4001), Range::new(1, against + 1)),
-            _ => panic!(),
-        };
-        let mut matching = self.clone();
-        matching.0.entry(xmas).and_modify(|ranges| {
-            *ranges = ranges.intersect_one(&matching_range);
-        });
-        let mut other = self.clone();
-        other.0.entry(xmas).and_modify(|ranges| {
-            *ranges = ranges.intersect_one(&other_range);
-        });
-        (matching, other)
+// Splits parts into those matching the comparison, and the rest.
+fn bisect(
+    parts: &AcceptableRanges,
+    xmas: char,
+    comparison: char,
+    against: isize,
+) -> (AcceptableRanges, AcceptableRanges) {
+    match comparison {
+        '<' => parts.split_at(&xmas, against),
+        '>' => {
+            let (other, matching) = parts.split_at(&xmas, against + 1);
+            (matching, other)
+        }
+        _ => panic!(),
     }
 }
 
@@ -52,7 +33,7 @@ enum Action {
 }
 
 impl FromStr for Action {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(match s {
@@ -63,6 +44,14 @@ impl FromStr for Action {
     }
 }
 
+// Parses the category at the start of a rule or rating.
+fn parse_xmas(s: &str) -> Result<char, ParseError> {
+    match s.chars().next() {
+        Some(c) if "xmas".contains(c) => Ok(c),
+        _ => Err(ParseError::new(s.get(..1).unwrap_or(s), "x, m, a or s")),
+    }
+}
+
 #[derive(Debug)]
 struct Rule {
     xmas: char,
@@ -72,14 +61,18 @@ struct Rule {
 }
 
 impl FromStr for Rule {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (m, action_str) = s.split_once(':').unwrap();
-        let xmas = m.chars().nth(0).unwrap();
-        let comparison = m.chars().nth(1).unwrap();
-        let against = m[2..].parse().unwrap();
-        let action = action_str.parse::<Action>().unwrap();
+        let (m, action_str) = split_once(s, ":")?;
+        let xmas = parse_xmas(m)?;
+        let comparison = match m.get(1..2) {
+            Some("<") => '<',
+            Some(">") => '>',
+            _ => return Err(ParseError::new(m.get(1..).unwrap_or(m), "'<' or '>'")),
+        };
+        let against = parse_num(&m[2..])?;
+        let action = action_str.parse::<Action>()?;
         Ok(Self {
             xmas,
             comparison,
@@ -118,22 +111,27 @@ impl Workflow {
 }
 
 #[derive(Debug)]
-struct Workflows(HashMap<String, Workflow>);
+pub struct Workflows(HashMap<String, Workflow>);
 
-fn parse_workflow_line(line: &str) -> (String, Workflow) {
-    let (name, rest) = line.split_once('{').unwrap();
+fn parse_workflow_line(line: &str) -> Result<(String, Workflow), ParseError> {
+    let (name, rest) = split_once(line, "{")?;
     let mut items: Vec<_> = rest.trim_end_matches('}').split(',').collect();
-    let default = items.pop().unwrap().parse().unwrap();
-    let rules = items.iter().map(|i| i.parse().unwrap()).collect();
+    // split() always yields at least one item.
+    let default = items.pop().unwrap().parse()?;
+    let rules = items.iter().map(|i| i.parse()).collect::<Result<_, _>>()?;
 
-    (name.to_string(), Workflow { rules, default })
+    Ok((name.to_string(), Workflow { rules, default }))
 }
 
 impl FromStr for Workflows {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.lines().map(parse_workflow_line).collect()))
+        Ok(Self(
+            s.lines()
+                .map(parse_workflow_line)
+                .collect::<Result<_, _>>()?,
+        ))
     }
 }
 
@@ -154,7 +152,7 @@ impl Workflows {
     fn count(&self, action: &Action, parts: &AcceptableRanges) -> usize {
         let name = match action {
             Action::Accept => {
-                return parts.combinations();
+                return parts.volume() as usize;
             }
             Action::Reject => {
                 return 0;
@@ -169,7 +167,7 @@ impl Workflows {
 
         for rule in workflow.rules.iter() {
             let (matching_parts, new_parts_left) =
-                parts_left.bisect(rule.xmas, rule.comparison, rule.against);
+                bisect(&parts_left, rule.xmas, rule.comparison, rule.against);
             count += self.count(&rule.action, &matching_parts);
             parts_left = new_parts_left;
         }
@@ -179,27 +177,27 @@ impl Workflows {
     }
 
     fn part2(&self) -> usize {
-        self.count(&Action::Send("in".to_string()), &AcceptableRanges::all())
+        self.count(&Action::Send("in".to_string()), &all_parts())
     }
 }
 
 #[derive(Debug)]
-struct Part(HashMap<char, isize>);
+pub struct Part(HashMap<char, isize>);
 
 impl FromStr for Part {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(HashMap::from_iter(
+        Ok(Self(
             s.trim_start_matches('{')
                 .trim_end_matches('}')
                 .split(',')
                 .map(|rating| {
-                    let xmas = rating.chars().next().unwrap();
-                    let value = rating[2..].parse().unwrap();
-                    (xmas, value)
-                }),
-        )))
+                    let (xmas, value) = split_once(rating, "=")?;
+                    Ok((parse_xmas(xmas)?, parse_num(value)?))
+                })
+                .collect::<Result<_, _>>()?,
+        ))
     }
 }
 
@@ -209,26 +207,36 @@ impl Part {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let (workflows_str, parts_str) = input.split("\n\n").collect_tuple().unwrap();
-    let workflows: Workflows = workflows_str.parse().unwrap();
-    let parts: Vec<Part> = parts_str
-        .lines()
-        .map(|line| line.parse().unwrap())
-        .collect();
-    // dbg!(&workflows);
-    // dbg!(&parts);
-    let part1: isize = parts
-        .iter()
-        .map(|part| {
-            if workflows.should_accept(part) {
-                part.sum()
-            } else {
-                0
-            }
-        })
-        .sum();
-    dbg!(&part1);
-    dbg!(workflows.part2());
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = (Workflows, Vec<Part>);
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let (workflows_str, parts_str) = split_once(input, "\n\n")?;
+        let workflows: Workflows = workflows_str.parse()?;
+        let parts: Vec<Part> = parts_str
+            .lines()
+            .map(|line| line.parse())
+            .collect::<Result<_, _>>()?;
+        Ok((workflows, parts))
+    }
+
+    fn part1((workflows, parts): &Self::Input) -> Answer {
+        let part1: isize = parts
+            .iter()
+            .map(|part| {
+                if workflows.should_accept(part) {
+                    part.sum()
+                } else {
+                    0
+                }
+            })
+            .sum();
+        part1.into()
+    }
+
+    fn part2((workflows, _): &Self::Input) -> Answer {
+        workflows.part2().into()
+    }
 }
@@ -1,12 +1,12 @@
 use std::collections::HashMap;
 
-use adv2023::read_input;
+use crate::{parse_num, split_once, Answer, ParseError, Solution};
 
 // Parses "<n> <color>".
-fn parse_cube_count(s: &str) -> (String, u32) {
-    let (count_str, color) = s.trim().split_once(' ').unwrap();
-    let count: u32 = count_str.parse().unwrap();
-    (color.to_string(), count)
+fn parse_cube_count(s: &str) -> Result<(String, u32), ParseError> {
+    let (count_str, color) = split_once(s.trim(), " ")?;
+    let count: u32 = parse_num(count_str)?;
+    Ok((color.to_string(), count))
 }
 
 #[derive(Debug, Clone)]
@@ -14,9 +14,11 @@ struct Cubes(HashMap<String, u32>);
 
 impl Cubes {
     // Parses "," delimited "<n> <color>".
-    fn parse(s: &str) -> Self {
-        Cubes(HashMap::from_iter(
-            s.split(',').map(|x| parse_cube_count(x)),
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        Ok(Cubes(
+            s.split(',')
+                .map(parse_cube_count)
+                .collect::<Result<_, _>>()?,
         ))
     }
 
@@ -38,18 +40,21 @@ impl Cubes {
 }
 
 #[derive(Debug)]
-struct Game {
+pub struct Game {
     id: u32,
     rounds: Vec<Cubes>,
 }
 
 impl Game {
-    fn parse(line: &str) -> Self {
-        let (game_and_id, rest) = line.split_once(":").unwrap();
-        let (_, id_str) = game_and_id.split_once(" ").unwrap();
-        let id: u32 = id_str.parse().unwrap();
-        let rounds = rest.split(';').map(|x| Cubes::parse(x)).collect();
-        Self { id, rounds }
+    fn parse(line: &str) -> Result<Self, ParseError> {
+        let (game_and_id, rest) = split_once(line, ":")?;
+        let (_, id_str) = split_once(game_and_id, " ")?;
+        let id: u32 = parse_num(id_str)?;
+        let rounds = rest
+            .split(';')
+            .map(Cubes::parse)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { id, rounds })
     }
 
     fn fits(&self, limits: &Cubes) -> bool {
@@ -74,16 +79,27 @@ impl Game {
     }
 }
 
-fn main() {
-    let input = read_input();
-    let games: Vec<Game> = input.lines().map(|line| Game::parse(line)).collect();
-    let limits = Cubes::parse("12 red, 13 green, 14 blue");
-    let part1: u32 = games
-        .iter()
-        .filter(|game| game.fits(&limits))
-        .map(|game| game.id)
-        .sum();
-    dbg!(part1);
-    let part2: u32 = games.iter().map(|game| game.power()).sum();
-    dbg!(part2);
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(Game::parse).collect()
+    }
+
+    fn part1(games: &Self::Input) -> Answer {
+        let limits = Cubes::parse("12 red, 13 green, 14 blue").unwrap();
+        let part1: u32 = games
+            .iter()
+            .filter(|game| game.fits(&limits))
+            .map(|game| game.id)
+            .sum();
+        part1.into()
+    }
+
+    fn part2(games: &Self::Input) -> Answer {
+        let part2: u32 = games.iter().map(|game| game.power()).sum();
+        part2.into()
+    }
 }
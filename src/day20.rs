@@ -1,9 +1,12 @@
 use std::{
     collections::{HashMap, VecDeque},
-    process::Output,
     str::FromStr,
 };
 
+use log::{debug, trace};
+
+use crate::{split_once, Answer, Graph, ParseError, Solution};
+
 #[derive(Debug, Clone)]
 enum NodeType {
     Broadcast,
@@ -15,11 +18,12 @@ enum NodeType {
 }
 
 impl NodeType {
-    fn from_char(c: char) -> Self {
-        match c {
-            '%' => Self::FlipFlop(false),
-            '&' => Self::Conjunction(HashMap::new()),
-            _ => panic!(),
+    // Parses the "%" or "&" prefix of the module name.
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        match s {
+            "%" => Ok(Self::FlipFlop(false)),
+            "&" => Ok(Self::Conjunction(HashMap::new())),
+            _ => Err(ParseError::new(s, "'%' or '&'")),
         }
     }
 }
@@ -32,16 +36,17 @@ struct Node {
 }
 
 impl FromStr for Node {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (type_and_name, dests) = s.split_once(" -> ").unwrap();
+        let (type_and_name, dests) = split_once(s, " -> ")?;
         let (name, node_type) = if type_and_name == "broadcaster" {
             (type_and_name.to_string(), NodeType::Broadcast)
         } else {
+            let prefix = type_and_name.get(..1).unwrap_or(type_and_name);
             (
-                type_and_name[1..].to_string(),
-                NodeType::from_char(type_and_name.chars().next().unwrap()),
+                type_and_name[prefix.len()..].to_string(),
+                NodeType::parse(prefix)?,
             )
         };
         let destinations = dests.split(", ").map(|s| s.to_string()).collect();
@@ -54,19 +59,19 @@ impl FromStr for Node {
 }
 
 #[derive(Debug, Clone)]
-struct Modules(HashMap<String, Node>);
+pub struct Modules(HashMap<String, Node>);
 
 impl FromStr for Modules {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut nodes: HashMap<String, Node> = s
             .lines()
             .map(|line| {
-                let node: Node = line.parse().unwrap();
-                (node.name.clone(), node)
+                let node: Node = line.parse()?;
+                Ok((node.name.clone(), node))
             })
-            .collect();
+            .collect::<Result<_, ParseError>>()?;
         nodes.insert(
             "output".to_string(),
             Node {
@@ -78,7 +83,6 @@ impl FromStr for Modules {
         let nodes_copy = nodes.clone();
         for source in nodes_copy.values() {
             for dest in &source.destinations {
-                dbg!(dest);
                 if nodes.contains_key(dest) {
                     if let NodeType::Conjunction(c) = &mut nodes.get_mut(dest).unwrap().node_type {
                         c.insert(source.name.clone(), false);
@@ -91,6 +95,23 @@ impl FromStr for Modules {
     }
 }
 
+impl Modules {
+    // Which modules send pulses to which, for inspection.
+    fn graph(&self) -> Graph<String> {
+        let mut graph = Graph::directed();
+        let mut names: Vec<&String> = self.0.keys().collect();
+        names.sort();
+        for name in names {
+            let from = graph.add_node(name.clone());
+            for dest in &self.0[name].destinations {
+                let to = graph.add_node(dest.clone());
+                graph.add_edge(from, to, 1);
+            }
+        }
+        graph
+    }
+}
+
 #[derive(Debug, Default)]
 struct Propagation {
     // (src, dest, high/low)
@@ -151,15 +172,28 @@ impl Propagation {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut modules: Modules = input.parse().unwrap();
-    dbg!(&modules);
-    let mut prop = Propagation::default();
-    for _ in 0..1000 {
-        prop.push_button(&mut modules);
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Modules;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let modules: Modules = input.parse()?;
+        trace!("{}", modules.graph().to_dot());
+        Ok(modules)
+    }
+
+    fn part1(modules: &Self::Input) -> Answer {
+        let mut modules = modules.clone();
+        let mut prop = Propagation::default();
+        for _ in 0..1000 {
+            prop.push_button(&mut modules);
+        }
+        debug!("high: {} low: {}", prop.high_sent, prop.low_sent);
+        (prop.high_sent * prop.low_sent).into()
+    }
+
+    fn part2(_modules: &Self::Input) -> Answer {
+        Answer::Unsolved
     }
-    dbg!(&prop.high_sent);
-    dbg!(&prop.low_sent);
-    dbg!(prop.high_sent * prop.low_sent);
 }
@@ -1,9 +1,11 @@
 use std::{collections::HashSet, str::FromStr};
 
-use adv2023::Pos;
+use log::debug;
+
+use crate::{search, Answer, AsPoint, Grid, ParseError, Pos, Solution};
 
 #[derive(Debug, Clone)]
-struct Map {
+pub struct Map {
     rocks: HashSet<Pos>,
     size: Pos,
     orig_size: Pos,
@@ -13,29 +15,19 @@ struct Map {
 type Reachable = HashSet<Pos>;
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let grid: Vec<Vec<char>> = s.lines().map(|line| line.chars().collect()).collect();
-        let size = Pos::new(grid.len(), grid[0].len());
-        let mut rocks = HashSet::<Pos>::new();
-        let mut start = None;
-        for y in 0..size.y {
-            for x in 0..size.x {
-                let pos = Pos::new(y, x);
-                match grid[y as usize][x as usize] {
-                    'S' => {
-                        start = Some(pos);
-                    }
-                    '#' => {
-                        rocks.insert(pos);
-                    }
-                    '.' => {}
-                    _ => panic!(),
-                }
-            }
-        }
-        let start = start.unwrap();
+        let grid = Grid::parse(s, |c| ".#S".contains(c).then_some(c), "'.', '#' or 'S'")?;
+        let size = grid.size();
+        let rocks = grid
+            .iter()
+            .filter(|(_, &c)| c == '#')
+            .map(|(pos, _)| pos)
+            .collect();
+        let start = grid
+            .find(&'S')
+            .ok_or_else(|| ParseError::new(s, "start tile 'S'"))?;
         Ok(Map {
             rocks,
             size,
@@ -46,43 +38,37 @@ impl FromStr for Map {
 }
 
 impl Map {
-    fn steps(&self, from: &Reachable) -> Reachable {
-        let mut next = Reachable::new();
-        for pos in from.iter() {
-            let poses_maybe = [
-                pos + &Pos::up(),
-                pos + &Pos::down(),
-                pos + &Pos::left(),
-                pos + &Pos::right(),
-            ];
-            for pos2 in poses_maybe {
-                if pos2.check_bounds(&self.size) && !self.rocks.contains(&pos2) {
-                    next.insert(pos2);
-                }
-            }
-        }
-        next
+    // Garden plots where a walk of exactly `steps` steps from the start can end.
+    fn reachable(&self, steps: usize) -> Reachable {
+        let neighbors = |pos: &Pos| {
+            pos.neighbors_within(&self.size)
+                .filter(|n| !self.rocks.contains(n))
+                .collect::<Vec<_>>()
+        };
+        search::reachable_in([self.start], neighbors, steps)
     }
 
+    #[allow(dead_code)]
     fn draw(&self, reachable: &Reachable, from: Pos, size: Pos) {
-        println!();
+        eprintln!();
         for y in (from.y as usize)..(from.y + size.y) as usize {
             for x in (from.x as usize)..(from.x + size.y) as usize {
                 let pos = Pos::new(y, x);
                 if self.rocks.contains(&pos) {
-                    print!("#");
+                    eprint!("#");
                 } else if pos == self.start {
-                    print!("S");
+                    eprint!("S");
                 } else if reachable.contains(&pos) {
-                    print!("O");
+                    eprint!("O");
                 } else {
-                    print!(".");
+                    eprint!(".");
                 }
             }
-            println!();
+            eprintln!();
         }
     }
 
+    #[allow(dead_code)]
     fn expand_right(&mut self, copies: usize) {
         for &Pos { x, y } in self.rocks.clone().iter() {
             for i in 1..=copies {
@@ -126,77 +112,102 @@ impl Map {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut map: Map = input.parse().unwrap();
+fn part1(map: &Map) -> usize {
+    map.reachable(64).len()
+}
+
+const PART2_STEPS: isize = 26501365;
+
+// Relies on the shape of the real input: a square of odd size with the start
+// in the middle of an empty row and column, and the steps ending on the edge
+// of a tile after an even number of whole tiles. None if the map isn't like
+// that.
+fn part2(map: &Map) -> Option<usize> {
+    let tile = map.orig_size.x;
+    let half = tile / 2;
+    let open_cross = !map
+        .rocks
+        .iter()
+        .any(|r| r.y == map.start.y || r.x == map.start.x);
+    if map.orig_size.y != tile
+        || tile % 2 == 0
+        || map.start != Pos::new(half, half)
+        || !open_cross
+        || (PART2_STEPS - half) % (2 * tile) != 0
+    {
+        return None;
+    }
+    let big_steps = ((PART2_STEPS - half) / tile) as usize;
+
+    let mut map = map.clone();
 
     let trial_big_steps = 2;
 
     map.expand(1 + trial_big_steps * 2);
-    let mut reachable = Reachable::from([map.start.clone()]);
-
-    let to = 131 * trial_big_steps + 65;
-    for _ in 0..to {
-        reachable = map.steps(&reachable);
-    }
-    dbg!(&reachable.len());
-    dbg!(&map.orig_size);
+    let reachable = map.reachable((tile * trial_big_steps + half) as usize);
+    debug!(
+        "reachable: {} orig_size: {:?}",
+        reachable.len(),
+        map.orig_size
+    );
     let starting = map.count_area(&reachable, 0, 0);
     let other = map.count_area(&reachable, 0, 1);
     let other_ = map.count_area(&reachable, 1, 0);
-    assert_eq!(other, other_);
+    if other != other_ {
+        return None;
+    }
 
-    dbg!(starting, other);
+    debug!("starting: {starting} other: {other}");
 
     let to_right_3_4 = map.count_area(&reachable, 0, trial_big_steps);
     let to_left_3_4 = map.count_area(&reachable, 0, -trial_big_steps);
     let to_up_3_4 = map.count_area(&reachable, -trial_big_steps, 0);
     let to_down_3_4 = map.count_area(&reachable, trial_big_steps, 0);
 
-    dbg!(to_right_3_4, to_left_3_4, to_up_3_4, to_down_3_4);
+    debug!("3/4: {to_right_3_4} {to_left_3_4} {to_up_3_4} {to_down_3_4}");
 
     let to_right_up_1_8 = map.count_area(&reachable, -1, trial_big_steps);
     let to_right_up_7_8 = map.count_area(&reachable, -1, trial_big_steps - 1);
     let to_right_up_1_8_ = map.count_area(&reachable, -trial_big_steps, 1);
-    assert_eq!(to_right_up_1_8, to_right_up_1_8_);
+    if to_right_up_1_8 != to_right_up_1_8_ {
+        return None;
+    }
 
     let to_left_up_1_8 = map.count_area(&reachable, -1, -trial_big_steps);
     let to_left_up_7_8 = map.count_area(&reachable, -1, -(trial_big_steps - 1));
     let to_left_up_1_8_ = map.count_area(&reachable, -trial_big_steps, -1);
-    assert_eq!(to_left_up_1_8, to_left_up_1_8_);
+    if to_left_up_1_8 != to_left_up_1_8_ {
+        return None;
+    }
 
     let to_left_down_1_8 = map.count_area(&reachable, 1, -trial_big_steps);
     let to_left_down_7_8 = map.count_area(&reachable, 1, -(trial_big_steps - 1));
     let to_left_down_1_8_ = map.count_area(&reachable, trial_big_steps, -1);
-    assert_eq!(to_left_down_1_8, to_left_down_1_8_);
+    if to_left_down_1_8 != to_left_down_1_8_ {
+        return None;
+    }
 
     let to_right_down_1_8 = map.count_area(&reachable, 1, trial_big_steps);
     let to_right_down_7_8 = map.count_area(&reachable, 1, trial_big_steps - 1);
     let to_right_down_1_8_ = map.count_area(&reachable, trial_big_steps, 1);
-    assert_eq!(to_right_down_1_8, to_right_down_1_8_);
-
-    dbg!(
-        to_right_up_1_8,
-        to_right_up_7_8,
-        to_left_up_1_8,
-        to_left_up_7_8,
-        to_left_down_1_8,
-        to_left_down_7_8,
-        to_right_down_1_8,
-        to_right_down_7_8
-    );
+    if to_right_down_1_8 != to_right_down_1_8_ {
+        return None;
+    }
 
-    let big_steps = 202300;
+    debug!(
+        "1/8, 7/8: {to_right_up_1_8} {to_right_up_7_8} {to_left_up_1_8} {to_left_up_7_8} \
+         {to_left_down_1_8} {to_left_down_7_8} {to_right_down_1_8} {to_right_down_7_8}"
+    );
 
     let starting_count = 1 + (4 * (big_steps - 2)) * (big_steps / 2) / 2;
     let other_count = (4 + 4 * (big_steps - 1)) * (big_steps / 2) / 2;
     let corner_7_8_count = big_steps - 1;
     let corner_1_8_count = big_steps;
 
-    dbg!(starting_count);
-    dbg!(other_count);
-    dbg!(corner_7_8_count);
-    dbg!(corner_1_8_count);
+    debug!(
+        "counts: starting {starting_count} other {other_count} \
+         7/8 {corner_7_8_count} 1/8 {corner_1_8_count}"
+    );
 
     let tot = starting_count * starting
         + other_count * other
@@ -205,9 +216,7 @@ fn main() {
             * (to_right_up_1_8 + to_left_up_1_8 + to_left_down_1_8 + to_right_down_1_8)
         + corner_7_8_count
             * (to_right_up_7_8 + to_left_up_7_8 + to_left_down_7_8 + to_right_down_7_8);
-    dbg!(tot);
 
-    dbg!(&reachable.len());
     // map.draw(&reachable, Pos::new(0, 0), map.size);
     // println!("\n\n");
     // map.draw(
@@ -215,4 +224,23 @@ fn main() {
     //     Pos::new(2 * map.orig_size.y, 2 * map.orig_size.x),
     //     map.orig_size,
     // );
+    Some(tot)
+}
+
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Map;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(map: &Self::Input) -> Answer {
+        part1(map).into()
+    }
+
+    fn part2(map: &Self::Input) -> Answer {
+        part2(map).map_or(Answer::Unsolved, Answer::from)
+    }
 }
@@ -1,226 +1,119 @@
-use itertools::Itertools;
-use std::{collections::HashMap, ops::RangeInclusive, str::FromStr};
+use std::str::FromStr;
 
-use adv2023::Pos3;
+use crate::{Answer, Cuboid, ParseError, Pos3, Solution};
 
 type BrickIdx = usize;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct Brick {
-    x: RangeInclusive<isize>,
-    y: RangeInclusive<isize>,
-    z: RangeInclusive<isize>,
-    deleted: bool,
+    cuboid: Cuboid,
 }
 
 #[derive(Debug, Clone)]
-struct World {
+pub struct World {
+    // Ordered by height once settled.
     bricks: Vec<Brick>,
-    pos_to_brick: HashMap<Pos3, BrickIdx>,
+    // Bricks each brick rests on.
+    supports: Vec<Vec<BrickIdx>>,
 }
 
-struct BrickIter {
-    brick: Brick,
-    x: isize,
-    y: isize,
-    z: isize,
-    done: bool,
-}
+impl FromStr for Brick {
+    type Err = ParseError;
 
-impl BrickIter {
-    fn new(brick: &Brick) -> Self {
-        Self {
-            brick: brick.clone(),
-            x: *brick.x.start(),
-            y: *brick.y.start(),
-            z: *brick.z.start(),
-            done: false,
-        }
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Brick { cuboid: s.parse()? })
     }
 }
 
-impl Iterator for BrickIter {
-    type Item = Pos3;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.done {
-            return None;
-        }
-        let item = Some(Pos3::new(self.x, self.y, self.z));
-        self.x += 1;
-        if self.x > *self.brick.x.end() {
-            self.x = *self.brick.x.start();
-            self.y += 1;
-        }
-        if self.y > *self.brick.y.end() {
-            self.y = *self.brick.y.start();
-            self.z += 1;
-        }
-        if self.z > *self.brick.z.end() {
-            self.done = true;
-        }
-        item
+impl Brick {
+    fn bottom(&self) -> isize {
+        self.cuboid.min.z()
     }
-}
-
-impl FromStr for Brick {
-    type Err = ();
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // 1,0,1~1,2,1
-        let (lhs, rhs) = s.split_once('~').unwrap();
-        let (x1, y1, z1) = lhs
-            .split(',')
-            .map(|x| x.parse::<isize>().unwrap())
-            .collect_tuple()
-            .unwrap();
-        let (x2, y2, z2) = rhs
-            .split(',')
-            .map(|x| x.parse::<isize>().unwrap())
-            .collect_tuple()
-            .unwrap();
-        // let (x2, y2, z2) = rhs.split(',').collect_tuple().unwrap();
-        Ok(Brick {
-            x: x1.min(x2)..=x1.max(x2),
-            y: y1.min(y2)..=y1.max(y2),
-            z: z1.min(z2)..=z1.max(z2),
-            deleted: false,
-        })
+    fn top(&self) -> isize {
+        self.cuboid.max.z()
     }
-}
 
-impl Brick {
-    fn blocks_iter(&self) -> BrickIter {
-        BrickIter::new(self)
+    /// Space straight under the brick, from height `z` up to just below it.
+    fn below(&self, z: isize) -> Cuboid {
+        let (min, max) = self.cuboid.project(2);
+        Cuboid::new(
+            Pos3::new([min[0], min[1], z]),
+            Pos3::new([max[0], max[1], self.bottom() - 1]),
+        )
     }
 }
 
 impl FromStr for World {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let bricks: Vec<Brick> = s
             .lines()
-            .map(|line| line.parse::<Brick>().unwrap())
-            .collect();
-        let mut pos_to_brick = HashMap::new();
-        for (i, b) in bricks.iter().enumerate() {
-            for pos in b.blocks_iter() {
-                pos_to_brick.insert(pos, i);
-            }
+            .map(|line| line.parse::<Brick>())
+            .collect::<Result<_, _>>()?;
+        if bricks.is_empty() {
+            return Err(ParseError::at_end(s, "bricks"));
         }
-
         Ok(Self {
             bricks,
-            pos_to_brick,
+            supports: vec![],
         })
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test() {
-        let brick: Brick = "0,0,0~1,2,1".parse().unwrap();
-        let t: Vec<_> = brick.blocks_iter().collect();
-        assert_eq!(
-            t,
-            vec![
-                Pos3 { x: 0, y: 0, z: 0 },
-                Pos3 { x: 1, y: 0, z: 0 },
-                Pos3 { x: 0, y: 1, z: 0 },
-                Pos3 { x: 1, y: 1, z: 0 },
-                Pos3 { x: 0, y: 2, z: 0 },
-                Pos3 { x: 1, y: 2, z: 0 },
-                Pos3 { x: 0, y: 0, z: 1 },
-                Pos3 { x: 1, y: 0, z: 1 },
-                Pos3 { x: 0, y: 1, z: 1 },
-                Pos3 { x: 1, y: 1, z: 1 },
-                Pos3 { x: 0, y: 2, z: 1 },
-                Pos3 { x: 1, y: 2, z: 1 },
-            ]
-        );
-    }
-}
-
 impl World {
-    /// Tries dropping a brick, returns true if dropped at all.
-    fn try_drop_brick(&mut self, idx: BrickIdx) -> bool {
-        let mut dropped = false;
-
-        loop {
-            for pos in self.bricks[idx].blocks_iter() {
-                if pos.z == 1 {
-                    return dropped;
-                }
-                if let Some(brick_below_idx) = self.pos_to_brick.get(&pos.below()) {
-                    if *brick_below_idx != idx {
-                        return dropped;
-                    }
-                }
-            }
-            for pos in self.bricks[idx].blocks_iter() {
-                assert!(self.pos_to_brick.remove(&pos).is_some());
-            }
-            self.bricks[idx].z =
-                (*self.bricks[idx].z.start() - 1)..=(*self.bricks[idx].z.end() - 1);
-            for pos in self.bricks[idx].blocks_iter() {
-                assert!(self.pos_to_brick.insert(pos, idx).is_none());
-            }
-
-            dropped = true;
-        }
-    }
-
-    fn try_drop_all_bricks(&mut self) -> bool {
-        let mut dropped = false;
+    /// Drops all bricks as far as they go, lowest first so that everything
+    /// underneath a brick has landed before it falls.
+    fn settle(&mut self) {
+        self.bricks.sort_by_key(Brick::bottom);
         for idx in 0..self.bricks.len() {
-            if !self.bricks[idx].deleted {
-                dropped |= self.try_drop_brick(idx);
+            let brick = &self.bricks[idx];
+            if brick.bottom() == 1 {
+                continue;
             }
+            let below = brick.below(1);
+            let landing = self.bricks[..idx]
+                .iter()
+                .filter(|b| b.cuboid.intersects(&below))
+                .map(|b| b.top() + 1)
+                .max()
+                .unwrap_or(1);
+            let drop = landing - brick.bottom();
+            let cuboid = &mut self.bricks[idx].cuboid;
+            *cuboid = cuboid.translate(Pos3::new([0, 0, drop]));
         }
-        dropped
-    }
-
-    fn drop_until_settles(&mut self) {
-        while self.try_drop_all_bricks() {}
-    }
-
-    fn delete_brick(&mut self, idx: usize) {
-        for pos in self.bricks[idx].blocks_iter() {
-            assert!(self.pos_to_brick.remove(&pos).is_some());
-        }
-        self.bricks[idx].deleted = true;
+        self.bricks.sort_by_key(Brick::bottom);
+
+        self.supports = self
+            .bricks
+            .iter()
+            .map(|brick| {
+                let layer = brick.below(brick.bottom() - 1);
+                (0..self.bricks.len())
+                    .filter(|&idx| self.bricks[idx].cuboid.intersects(&layer))
+                    .collect()
+            })
+            .collect();
     }
 
     fn part1_count_disintegratable_bricks(&self) -> usize {
-        let mut count = 0usize;
-
-        for idx in 0..self.bricks.len() {
-            let mut tmp_world = self.clone();
-            tmp_world.delete_brick(idx);
-            if !tmp_world.try_drop_all_bricks() {
-                count += 1;
-            }
-        }
-        count
+        (0..self.bricks.len())
+            .filter(|&idx| !self.supports.iter().any(|s| s == &[idx]))
+            .count()
     }
 
     fn part2_count_fallen_bricks(&self) -> usize {
         let mut count = 0usize;
 
         for idx in 0..self.bricks.len() {
-            let mut tmp_world = self.clone();
-            tmp_world.delete_brick(idx);
-            tmp_world.drop_until_settles();
-
-            for idx2 in 0..self.bricks.len() {
-                if idx2 == idx {
-                    continue;
-                }
-                if self.bricks[idx2] != tmp_world.bricks[idx2] {
+            // Bricks only rest on lower ones, which come first.
+            let mut fallen = vec![false; self.bricks.len()];
+            fallen[idx] = true;
+            for idx2 in idx + 1..self.bricks.len() {
+                let supports = &self.supports[idx2];
+                if !supports.is_empty() && supports.iter().all(|&s| fallen[s]) {
+                    fallen[idx2] = true;
                     count += 1;
                 }
             }
@@ -229,11 +122,39 @@ impl World {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut world: World = input.parse().unwrap();
-    // dbg!(&world.try_drop_brick(2));
-    world.drop_until_settles();
-    dbg!(world.part1_count_disintegratable_bricks());
-    dbg!(world.part2_count_fallen_bricks());
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = World;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut world: World = input.parse()?;
+        world.settle();
+        Ok(world)
+    }
+
+    fn part1(world: &Self::Input) -> Answer {
+        world.part1_count_disintegratable_bricks().into()
+    }
+
+    fn part2(world: &Self::Input) -> Answer {
+        world.part2_count_fallen_bricks().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test() {
+        let mut world: World = "1,0,1~1,2,1\n0,0,3~2,0,3\n1,1,7~1,1,9".parse().unwrap();
+        assert_eq!(world.bricks[1].below(1).to_string(), "0,0,1~2,0,2");
+        world.settle();
+        let settled: Vec<_> = world.bricks.iter().map(|b| b.cuboid.to_string()).collect();
+        assert_eq!(settled, vec!["1,0,1~1,2,1", "0,0,2~2,0,2", "1,1,2~1,1,4"]);
+        assert_eq!(world.supports, vec![vec![], vec![0], vec![0]]);
+        assert_eq!(world.part1_count_disintegratable_bricks(), 2);
+        assert_eq!(world.part2_count_fallen_bricks(), 2);
+    }
 }
@@ -1,254 +1,167 @@
-use std::{
-    cmp::max,
-    collections::{BTreeSet, HashMap, HashSet},
-};
+use std::cmp::max;
 
-use adv2023::Pos;
+use log::trace;
 
-type NodeIdx = usize;
+use crate::{Answer, Direction, Graph, Grid, NodeId, ParseError, Pos, Solution};
 
-#[derive(Debug, Clone)]
-struct Edge {
+fn recurse(
+    graph: &Graph<Pos>,
+    node_idx: NodeId,
+    mut visited_mask: u64,
     length: usize,
-    connects_to: NodeIdx,
-}
-
-#[derive(Clone, Debug, Default)]
-struct Node {
-    pos: Pos,
-    outgoing: Vec<Edge>,
-    incoming: HashSet<NodeIdx>,
-    longest_distance: usize,
-}
-
-#[derive(Clone, Default, Debug)]
-struct Graph {
-    nodes: Vec<Node>,
-    pos_to_node: HashMap<Pos, NodeIdx>,
-}
-
-impl Graph {
-    fn insert_or_get_node(&mut self, pos: &Pos) -> usize {
-        if let Some(idx) = self.pos_to_node.get(&pos) {
-            return *idx;
-        }
-        let node = Node {
-            pos: pos.clone(),
-            ..Default::default()
-        };
-        let idx = self.nodes.len();
-        self.nodes.push(node);
-        self.pos_to_node.insert(pos.clone(), idx);
-        idx
+) -> Option<usize> {
+    if node_idx == (graph.len() - 1) {
+        // assumes end is last idx
+        return Some(length);
     }
-
-    fn add_edge(&mut self, from: NodeIdx, connects_to: NodeIdx, length: usize) {
-        self.nodes[from].outgoing.push(Edge {
-            length,
-            connects_to,
-        });
-        assert!(self.nodes[connects_to].incoming.insert(from));
+    if ((1 << node_idx) & visited_mask) != 0 {
+        // seen already
+        return None;
     }
-
-    fn dump_nodes(&self) {
-        println!("digraph G {{");
-        for (i, node) in self.nodes.iter().enumerate() {
-            print!("  N{i} -> {{");
-            for edge in &node.outgoing {
-                print!("N{} ", edge.connects_to);
-            }
-            println!("}}");
+    let mut out: Option<usize> = None;
+    visited_mask |= 1 << node_idx;
+    for edge in graph.edges(node_idx) {
+        if let Some(result_length) = recurse(graph, edge.to, visited_mask, length + edge.weight) {
+            out = Some(max(out.unwrap_or_default(), result_length));
         }
-        println!("}}");
-    }
-
-    fn make_bidirectional(&mut self) {
-        for (i, node) in self.nodes.clone().iter().enumerate() {
-            for edge in &node.outgoing {
-                self.add_edge(edge.connects_to, i, edge.length);
-            }
-        }
-    }
-
-    fn recurse(&self, node_idx: NodeIdx, mut visited_mask: u64, length: usize) -> Option<usize> {
-        if node_idx == (self.nodes.len() - 1) {
-            // assumes end is last idx
-            return Some(length);
-        }
-        if ((1 << node_idx) & visited_mask) != 0 {
-            // seen already
-            return None;
-        }
-        let mut out: Option<usize> = None;
-        visited_mask |= 1 << node_idx;
-        for edge in &self.nodes[node_idx].outgoing {
-            if let Some(result_length) =
-                self.recurse(edge.connects_to, visited_mask, length + edge.length)
-            {
-                out = Some(max(out.unwrap_or_default(), result_length));
-            }
-        }
-        out
     }
+    out
+}
 
-    fn brute_force_longest_path(&self) -> usize {
-        // assumes going from 0 to last idx.
-        self.recurse(0, 0, 0).unwrap()
+// None if the end can't be reached, or there are too many nodes to track
+// in a u64 visited mask.
+fn brute_force_longest_path(graph: &Graph<Pos>) -> Option<usize> {
+    if graph.len() > 64 {
+        return None;
     }
+    // assumes going from 0 to last idx.
+    recurse(graph, 0, 0, 0)
 }
 
-#[derive(Default, Debug, Clone)]
-struct Problem {
-    map: Vec<Vec<char>>,
-    map_size: Pos,
+#[derive(Debug, Clone)]
+pub struct Problem {
+    map: Grid<char>,
     start: Pos,
     end: Pos,
-    graph: Graph,
+    // Junctions, start and end, with the corridors between them.
+    graph: Graph<Pos>,
+    // Same, with the slopes walkable both ways.
+    graph_without_slopes: Graph<Pos>,
 }
 
 impl Problem {
-    fn new(input: &str) -> Self {
-        let map = input
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<_>>();
-        let map_size = Pos::new(map.len(), map[0].len());
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let map = Grid::parse(
+            input,
+            |c| ".#<>^v".contains(c).then_some(c),
+            "'.', '#' or a slope",
+        )?;
         let start = Pos::new(0, 1);
-        let end = map_size + Pos::new(-1, -2);
-        Self {
+        let end = map.size() + Pos::new(-1, -2);
+        if map.get(start) != Some(&'.') || map.get(end) != Some(&'.') {
+            return Err(ParseError::new(input, "open start and end tiles"));
+        }
+        Ok(Self {
             map,
-            map_size,
             start,
             end,
-            ..Default::default()
-        }
+            graph: Graph::directed(),
+            graph_without_slopes: Graph::undirected(),
+        })
     }
 
     fn get_tile(&self, pos: &Pos) -> char {
-        self.map[pos.y as usize][pos.x as usize]
+        self.map[*pos]
     }
 
-    // Walks the edge, returns next node id and path length, unless dead-ends.
-    fn walk_edge(&mut self, mut pos: Pos, mut dir: Pos) -> Option<(NodeIdx, usize)> {
-        // println!("walk_edge, pos: {pos:?} dir: {dir:?}");
-        let mut length = 1;
-        loop {
-            if let Some(node_idx) = self.graph.pos_to_node.get(&pos) {
-                // println!("ended at node {node_idx}, length {length}");
-                return Some((*node_idx, length));
-            }
-
-            let mut possible_dirs = match self.get_tile(&pos) {
-                '.' => vec![Pos::left(), Pos::right(), Pos::up(), Pos::down()],
-                '>' => vec![Pos::right()],
-                '<' => vec![Pos::left()],
-                'v' => vec![Pos::down()],
-                '^' => vec![Pos::up()],
-                _ => panic!(),
-            };
-            // dbg!(&possible_dirs);
-            // Remove directions that would take us back to where we just came or go into walls.
-            possible_dirs.retain(|new_dir| {
-                new_dir != &dir.opposite() && self.get_tile(&(&pos + new_dir)) != '#'
-            });
-            // dbg!(&possible_dirs);
-            if possible_dirs.is_empty() {
-                // println!("ended at a dead-end");
-                return None;
-            }
-            assert!(possible_dirs.len() == 1);
-            dir = possible_dirs[0];
-            pos += &dir;
-            length += 1;
+    // Every open tile, with steps going downhill only on slopes unless
+    // they're ignored, making the graph undirected.
+    fn tile_graph(&self, slopes: bool) -> Graph<Pos> {
+        let open: Vec<Pos> = self
+            .map
+            .iter()
+            .filter(|(_, &c)| c != '#')
+            .map(|(pos, _)| pos)
+            .collect();
+        let mut graph = if slopes {
+            Graph::directed()
+        } else {
+            Graph::undirected()
+        };
+        for &pos in &open {
+            graph.add_node(pos);
         }
-    }
-
-    fn find_nodes(&mut self) {
-        self.graph.insert_or_get_node(&self.start);
-        for y in 1..(self.map_size.y as usize - 1) {
-            for x in 1..(self.map_size.x as usize - 1) {
-                let pos = Pos::new(y, x);
-                if self.get_tile(&pos) == '#' {
-                    continue;
-                }
-                let walls = pos
-                    .orthogonal_neighbors()
-                    .iter()
-                    .filter(|pos| self.get_tile(pos) == '#')
-                    .count();
-                if walls < 2 {
-                    // println!("node at {pos:?}");
-                    self.graph.insert_or_get_node(&pos);
+        for (from, &pos) in open.iter().enumerate() {
+            let dirs = match self.get_tile(&pos) {
+                '.' => Direction::ORTHOGONAL.to_vec(),
+                _ if !slopes => Direction::ORTHOGONAL.to_vec(),
+                slope => vec![Direction::from_char(slope).expect("slope")],
+            };
+            for dir in dirs {
+                match graph.id(&(pos + dir)) {
+                    // Undirected edges go both ways already.
+                    Some(to) if slopes || from < to => graph.add_edge(from, to, 1),
+                    _ => {}
                 }
             }
         }
-        self.graph.insert_or_get_node(&self.end);
+        graph
     }
 
-    fn find_edges(&mut self) {
-        // For each node, consider each outgoing path.
-        for node_idx in 0..self.graph.nodes.len() {
-            let node_pos = self.graph.nodes[node_idx].pos;
-            for dir in [Pos::up(), Pos::left(), Pos::down(), Pos::right()] {
-                let n_pos = node_pos + dir;
-                if !n_pos.check_bounds(&self.map_size) {
-                    continue;
-                }
-                if self.get_tile(&n_pos) == '#' {
-                    continue;
-                }
-                // println!("node_idx: {node_idx}, will look at edge starting at {n_pos:?}");
-                if let Some((connects_to, length)) = self.walk_edge(n_pos, dir) {
-                    self.graph.add_edge(node_idx, connects_to, length);
-                }
-            }
-        }
+    fn build(&mut self) {
+        self.graph = self.contract(&self.tile_graph(true));
+        self.graph_without_slopes = self.contract(&self.tile_graph(false));
+        trace!("{}", self.graph.to_dot());
     }
 
-    fn build(&mut self) {
-        self.find_nodes();
-        self.find_edges();
+    // Junctions, start and end, with the corridors between them.
+    fn contract(&self, tiles: &Graph<Pos>) -> Graph<Pos> {
+        // Ids follow the tiles row by row, so start stays first and end last.
+        tiles.contract(|id| {
+            let pos = *tiles.node(id);
+            let open = self
+                .map
+                .neighbors(pos)
+                .filter(|n| self.get_tile(n) != '#')
+                .count();
+            pos == self.start || pos == self.end || open > 2
+        })
     }
 
-    fn find_longest_directional_path(&mut self) -> usize {
-        let mut queue = BTreeSet::<NodeIdx>::from([0]);
-        while let Some(node_idx) = queue.pop_first() {
-            assert!(self.graph.nodes[node_idx].incoming.is_empty());
-            let distance = self.graph.nodes[node_idx].longest_distance;
-            let outgoing_edges = self.graph.nodes[node_idx].outgoing.clone();
-            for Edge {
-                length,
-                connects_to,
-            } in outgoing_edges
-            {
-                let other_node = &mut self.graph.nodes[connects_to];
-
-                let new_distance = distance + length;
-                if new_distance > other_node.longest_distance {
-                    other_node.longest_distance = new_distance;
-                }
-                assert!(other_node.incoming.remove(&node_idx));
-                if other_node.incoming.is_empty() {
-                    queue.insert(connects_to);
-                }
+    // None if the end can't be reached, or the slopes allow a loop.
+    fn find_longest_directional_path(&self) -> Option<usize> {
+        let mut longest = vec![None; self.graph.len()];
+        longest[0] = Some(0);
+        for from in self.graph.topological_order()? {
+            let Some(length) = longest[from] else {
+                continue;
+            };
+            for edge in self.graph.edges(from) {
+                longest[edge.to] = max(longest[edge.to], Some(length + edge.weight));
             }
         }
-        self.graph.nodes.last().unwrap().longest_distance
+        *longest.last()?
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut problem = Problem::new(&input);
-    problem.build();
-    problem.graph.dump_nodes();
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Problem;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let mut problem = Problem::new(input)?;
+        problem.build();
+        Ok(problem)
+    }
 
-    let mut graph2 = problem.graph.clone();
-    let part1 = problem.find_longest_directional_path();
-    dbg!(&part1);
+    fn part1(problem: &Self::Input) -> Answer {
+        let longest = problem.find_longest_directional_path();
+        longest.map_or(Answer::Unsolved, Answer::from)
+    }
 
-    graph2.make_bidirectional();
-    graph2.dump_nodes();
-    let part2 = graph2.brute_force_longest_path();
-    dbg!(&part2);
+    fn part2(problem: &Self::Input) -> Answer {
+        let longest = brute_force_longest_path(&problem.graph_without_slopes);
+        longest.map_or(Answer::Unsolved, Answer::from)
+    }
 }
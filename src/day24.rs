@@ -1,7 +1,12 @@
-use std::{ops::RangeInclusive, str::FromStr};
+use std::str::FromStr;
 
-use adv2023::{Pos, Pos3};
-use itertools::Itertools;
+use log::{debug, trace};
+use num::{BigInt, BigRational};
+
+use crate::{
+    linalg::{Matrix, Rational, Solved},
+    parse_num, split_once, Answer, ParseError, Pos3, Solution,
+};
 
 #[derive(Debug, Clone, Copy)]
 struct Hailstone {
@@ -10,43 +15,39 @@ struct Hailstone {
 }
 
 #[derive(Debug)]
-struct Problem {
-    test_area: (f64, f64),
+pub struct Problem {
+    test_area: (i128, i128),
     hailstones: Vec<Hailstone>,
 }
 
 impl FromStr for Hailstone {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (lhs, rhs) = s.split_once(" @ ").unwrap();
+        let (lhs, rhs) = split_once(s, " @ ")?;
 
-        let pos = Pos3::from_iter(lhs.split(",").map(|x| x.trim().parse::<isize>().unwrap()));
-        let vel = Pos3::from_iter(rhs.split(",").map(|x| x.trim().parse::<isize>().unwrap()));
+        let pos = lhs.parse()?;
+        let vel = rhs.parse()?;
 
         Ok(Hailstone { pos, vel })
     }
 }
 
 impl FromStr for Problem {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         // Note: I added the coordinate limits to input files.
-        let (limits, hailstone_lines) = s.trim().split_once("\n\n").unwrap();
+        let (limits, hailstone_lines) = split_once(s.trim(), "\n\n")?;
         // dbg!(&limits, &hailstone_lines);
 
-        let (from, to) = limits
-            .split(' ')
-            .map(|x| x.parse::<f64>().unwrap())
-            .collect_tuple()
-            .unwrap();
-        let test_area = (from, to);
+        let (from, to) = split_once(limits, " ")?;
+        let test_area = (parse_num(from)?, parse_num(to)?);
 
         let hailstones: Vec<Hailstone> = hailstone_lines
             .lines()
-            .map(|line| line.parse().unwrap())
-            .collect();
+            .map(|line| line.parse())
+            .collect::<Result<_, _>>()?;
         Ok(Problem {
             test_area,
             hailstones,
@@ -55,54 +56,70 @@ impl FromStr for Problem {
 }
 
 impl Hailstone {
-    fn find_future_2d_intersection_with(&self, other: &Hailstone) -> Option<(f64, f64)> {
-        assert_ne!(self.vel.y, 0);
-        let y10 = self.pos.y as f64;
-        let y1v = self.vel.y as f64;
-        let x10 = self.pos.x as f64;
-        let x1v = self.vel.x as f64;
-        let y20 = other.pos.y as f64;
-        let y2v = other.vel.y as f64;
-        let x20 = other.pos.x as f64;
-        let x2v = other.vel.x as f64;
-
-        let denom = y2v - (y1v * x2v / x1v);
-        if denom.abs() < 0.0000001 {
-            // parallel?
+    // Where the paths of both cross in the x-y plane, if they do so in the
+    // future for both.
+    fn find_future_2d_intersection_with(&self, other: &Hailstone) -> Option<(Rational, Rational)> {
+        // pos + vel * t = other.pos + other.vel * s
+        let int = |v: isize| Rational::from(v as i128);
+        let a: Matrix<Rational> = Matrix::new(vec![
+            vec![int(self.vel.x()), int(-other.vel.x())],
+            vec![int(self.vel.y()), int(-other.vel.y())],
+        ]);
+        let b = [
+            int(other.pos.x() - self.pos.x()),
+            int(other.pos.y() - self.pos.y()),
+        ];
+        // Parallel paths never cross at a single point.
+        let Solved::Unique(ts) = a.solve(&b) else {
             return None;
-        }
-
-        let t2 = (y10 + y1v * ((x20 - x10) / x1v) - y20) / denom;
-        if t2 < 0.0 {
+        };
+        if ts.iter().any(|t| *t < Rational::from(0)) {
             return None;
         }
-        let t1 = (x20 + x2v * t2 - x10) / x1v;
-        if t1 < 0.0 {
-            return None;
-        }
-        let x = x20 + x2v * t2;
-        let y = y20 + y2v * t2;
-        // (x, y)
+        let x = int(self.pos.x()) + int(self.vel.x()) * ts[0];
+        let y = int(self.pos.y()) + int(self.vel.y()) * ts[0];
         Some((x, y))
     }
+
+    // Whether a rock thrown from `pos` at `vel` hits this hailstone at some
+    // time after it's thrown.
+    fn is_hit_by(&self, pos: Pos3, vel: Pos3) -> bool {
+        // pos + vel * t = self.pos + self.vel * t, on every axis.
+        let (gap, closing) = (pos - self.pos, self.vel - vel);
+        let mut time = None;
+        for axis in 0..3 {
+            if closing[axis] == 0 {
+                if gap[axis] != 0 {
+                    return false;
+                }
+                continue;
+            }
+            if gap[axis] % closing[axis] != 0 {
+                return false;
+            }
+            let t = gap[axis] / closing[axis];
+            if t <= 0 || time.is_some_and(|time| time != t) {
+                return false;
+            }
+            time = Some(t);
+        }
+        true
+    }
 }
 
 impl Problem {
     fn part1_count_intersections(&self) -> usize {
+        let (from, to) = (
+            Rational::from(self.test_area.0),
+            Rational::from(self.test_area.1),
+        );
+        let in_area = |v: &Rational| from <= *v && *v <= to;
         let mut count = 0usize;
         for i in 0..self.hailstones.len() {
             for j in (i + 1)..self.hailstones.len() {
-                let res1 = self.hailstones[i].find_future_2d_intersection_with(&self.hailstones[j]);
-                // let res2 = self.hailstones[j].find_future_2d_intersection_with(&self.hailstones[i]);
-                // assert_eq!(res1.is_some(), res2.is_some());
-
-                if let Some((x, y)) = res1 {
-                    if x >= self.test_area.0
-                        && x <= self.test_area.1
-                        && y >= self.test_area.0
-                        && y <= self.test_area.1
-                    {
-                        // dbg!(i, j, x, y);
+                let res = self.hailstones[i].find_future_2d_intersection_with(&self.hailstones[j]);
+                if let Some((x, y)) = res {
+                    if in_area(&x) && in_area(&y) {
                         count += 1;
                     }
                 }
@@ -110,11 +127,100 @@ impl Problem {
         }
         count
     }
+
+    // Position and velocity of a rock thrown to hit all of `hailstones`,
+    // if they determine one.
+    fn throw(hailstones: &[Hailstone]) -> Option<(Pos3, Pos3)> {
+        // The rock at P with velocity V hits hailstone i if (P - p_i) and
+        // (V - v_i) are parallel, so their cross product is zero:
+        //   P x V - P x v_i - p_i x V + p_i x v_i = 0
+        // Subtracting this for two hailstones gets rid of P x V, leaving
+        //   P x (v_j - v_i) + (p_j - p_i) x V = p_j x v_j - p_i x v_i
+        // which is linear, with three equations for each pair. The products
+        // of positions and velocities still fit, but eliminating with them
+        // needs big numbers.
+        let cross = |a: Pos3, b: Pos3| {
+            Pos3::new([
+                a.y() * b.z() - a.z() * b.y(),
+                a.z() * b.x() - a.x() * b.z(),
+                a.x() * b.y() - a.y() * b.x(),
+            ])
+        };
+        let big = |v: isize| BigRational::from_integer(BigInt::from(v));
+        let (h0, rest) = hailstones.split_first()?;
+        let mut rows = vec![];
+        let mut values = vec![];
+        for h in rest {
+            let d = h.vel - h0.vel;
+            let e = h.pos - h0.pos;
+            rows.push([0, d.z(), -d.y(), 0, -e.z(), e.y()]);
+            rows.push([-d.z(), 0, d.x(), e.z(), 0, -e.x()]);
+            rows.push([d.y(), -d.x(), 0, -e.y(), e.x(), 0]);
+            let c = cross(h.pos, h.vel) - cross(h0.pos, h0.vel);
+            values.extend(c.0.map(big));
+        }
+        let a = Matrix::new(rows.iter().map(|r| r.map(big).to_vec()).collect());
+        let Solved::Unique(x) = a.solve(&values) else {
+            return None;
+        };
+        // The rock only hits at whole nanoseconds if all of these are whole.
+        let x: Vec<isize> = x
+            .iter()
+            .map(|v| {
+                v.is_integer()
+                    .then(|| isize::try_from(v.to_integer()).ok())?
+            })
+            .collect::<Option<_>>()?;
+        Some((Pos3::new([x[0], x[1], x[2]]), Pos3::new([x[3], x[4], x[5]])))
+    }
+
+    // None if no rock hits all the hailstones.
+    fn part2_throw_rock(&self) -> Option<isize> {
+        // Three hailstones are enough, unless their paths are too alike. The
+        // rock they pin down still has to hit all the others.
+        let (pos, vel) = self
+            .hailstones
+            .windows(3)
+            .filter_map(Self::throw)
+            .find(|&(pos, vel)| self.hailstones.iter().all(|h| h.is_hit_by(pos, vel)))?;
+        debug!("throwing from {pos:?} at {vel:?}");
+        Some(pos.x() + pos.y() + pos.z())
+    }
+}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Problem;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let problem: Problem = input.parse()?;
+        trace!("{problem:?}");
+        Ok(problem)
+    }
+
+    fn part1(problem: &Self::Input) -> Answer {
+        problem.part1_count_intersections().into()
+    }
+
+    fn part2(problem: &Self::Input) -> Answer {
+        problem
+            .part2_throw_rock()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let problem: Problem = input.parse().unwrap();
-    dbg!(&problem);
-    dbg!(&problem.part1_count_intersections());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_hit_by() {
+        let h: Hailstone = "19, 13, 30 @ -2,  1, -2".parse().unwrap();
+        let (pos, vel) = (Pos3::new([24, 13, 10]), Pos3::new([-3, 1, 2]));
+        assert!(h.is_hit_by(pos, vel));
+        assert!(!h.is_hit_by(pos, Pos3::new([-3, 1, 3])));
+        // Only before it's thrown.
+        assert!(!h.is_hit_by(pos + vel * 6, vel));
+    }
 }
@@ -1,8 +1,8 @@
 use itertools::Itertools;
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    str::FromStr,
-};
+use log::{debug, trace};
+use std::str::FromStr;
+
+use crate::{split_once, Answer, DisjointSet, Graph, ParseError, Solution};
 
 #[derive(Copy, Clone, Hash, PartialEq, Eq)]
 struct Node((char, char, char));
@@ -17,141 +17,84 @@ impl std::fmt::Debug for Node {
 }
 
 impl Node {
-    fn from(s: &str) -> Self {
-        assert_eq!(s.len(), 3);
-        Self(s.chars().collect_tuple().unwrap())
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        s.chars()
+            .collect_tuple()
+            .map(Self)
+            .ok_or_else(|| ParseError::new(s, "3 letter name"))
     }
 }
 
-#[derive(Debug, Default, Clone)]
-struct Graph {
-    edges: HashMap<Node, HashSet<Node>>,
-}
-
-impl Graph {
-    fn add_edge(&mut self, lhs: Node, rhs: Node) {
-        self.edges.entry(lhs).or_default().insert(rhs);
-        self.edges.entry(rhs).or_default().insert(lhs);
-    }
+#[derive(Debug, Clone)]
+pub struct Wiring(Graph<Node>);
 
-    // https://en.wikipedia.org/wiki/Edmonds%E2%80%93Karp_algorithm
+impl FromStr for Wiring {
+    type Err = ParseError;
 
-    fn find_path(&self, from: Node, to: Node) -> Option<Vec<Node>> {
-        let mut queue = VecDeque::<Node>::from([from]);
-        let mut pred = HashMap::<Node, Node>::new();
-        'outer: while let Some(cur) = queue.pop_front() {
-            for e in &self.edges[&cur] {
-                if !pred.contains_key(&e) && *e != from {
-                    pred.insert(*e, cur);
-                    if *e == to {
-                        break 'outer;
-                    }
-                    queue.push_back(*e);
-                }
-            }
-        }
-        if !pred.contains_key(&to) {
-            None
-        } else {
-            let mut out = vec![];
-            let mut cur = to;
-            while pred.contains_key(&cur) {
-                out.push(cur);
-                cur = pred[&cur];
-            }
-            out.reverse();
-            Some(out)
-        }
-    }
-
-    fn find_reachable(&self, from: Node) -> HashSet<Node> {
-        let mut queue = VecDeque::<Node>::from([from]);
-        let mut reachable = HashSet::<Node>::from([from]);
-        while let Some(cur) = queue.pop_front() {
-            for e in &self.edges[&cur] {
-                if !reachable.contains(e) {
-                    reachable.insert(*e);
-                    queue.push_back(*e);
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut graph = Graph::undirected();
+        // pzl: lsr hfx nvd
+        for line in s.lines() {
+            let (lhs, rhs) = split_once(line, ": ")?;
+            let a = graph.add_node(Node::parse(lhs)?);
+            for b in rhs.split_whitespace() {
+                let b = graph.add_node(Node::parse(b)?);
+                if !graph.has_edge(a, b) {
+                    graph.add_edge(a, b, 1);
                 }
             }
         }
-        reachable
-    }
-
-    fn take_edge(&mut self, from: Node, to: Node) {
-        if self.edges[&to].contains(&from) {
-            // println!("removing {:?}->{:?}", from, to);
-            self.edges.get_mut(&from).unwrap().remove(&to);
-        } else {
-            // println!("adding {:?}->{:?}", to, from);
-            self.edges.get_mut(&to).unwrap().insert(from);
+        if graph.len() < 2 {
+            return Err(ParseError::at_end(s, "components to split"));
         }
-    }
-
-    fn find_all_non_overlapping_paths(&mut self, from: Node, to: Node) -> usize {
-        let mut paths = 0;
-        loop {
-            match self.find_path(from, to) {
-                Some(path) => {
-                    // dbg!(&path);
-                    let mut cur = from;
-                    for next in path {
-                        self.take_edge(cur, next);
-                        cur = next;
-                    }
-                }
-                None => break,
-            }
-            paths += 1;
-        }
-        // dbg!(paths);
-        paths
+        Ok(Wiring(graph))
     }
 }
 
-impl FromStr for Graph {
-    type Err = ();
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut graph = Graph::default();
-        // pzl: lsr hfx nvd
-        s.lines().for_each(|line| {
-            let (lhs, rhs) = line.split_once(": ").unwrap();
-            let a = Node::from(lhs);
-            rhs.split_whitespace()
-                .for_each(|b| graph.add_edge(a, Node::from(b)));
-        });
-        Ok(graph)
+// The puzzle promises the components are connected by this many wires.
+const WIRES: usize = 3;
+
+fn part1(Wiring(graph): &Wiring) -> usize {
+    let cut = graph.min_cut().expect("no components");
+    let wires: Vec<_> = cut
+        .edges
+        .iter()
+        .map(|&(a, b)| (graph.node(a), graph.node(b)))
+        .collect();
+    debug!("cutting {wires:?}");
+    assert_eq!(cut.value, WIRES);
+
+    // Without the cut wires, the rest should fall apart into the two sides.
+    let mut groups = DisjointSet::new(graph.len());
+    for (a, edge) in graph.all_edges() {
+        if !cut.edges.contains(&(a, edge.to)) {
+            groups.union(a, edge.to);
+        }
     }
+    assert_eq!(groups.count(), 2);
+    let (group1, group2) = (cut.sides.0.len(), cut.sides.1.len());
+    assert_eq!(groups.size(cut.sides.0[0]), group1);
+    debug!("groups: {group1} {group2}");
+    group1 * group2
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let graph: Graph = input.parse().unwrap();
-    dbg!(&graph);
+pub struct Day25;
 
-    // Pick an arbitrary starting node.
-    let from = *graph.edges.keys().next().unwrap();
+impl Solution for Day25 {
+    type Input = Wiring;
 
-    let to_candidates: Vec<Node> = graph.edges.keys().cloned().collect();
-
-    for to in to_candidates {
-        if to == from {
-            continue;
-        }
-        let mut tmp_graph = graph.clone();
-        let path_count = tmp_graph.find_all_non_overlapping_paths(from, to);
-        if path_count > 3 {
-            continue;
-        }
-        assert_eq!(path_count, 3);
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let wiring: Wiring = input.parse()?;
+        trace!("{}", wiring.0.to_dot());
+        Ok(wiring)
+    }
 
-        // Now, any nodes that we can still reach, are in one partition.
-        let reachable = tmp_graph.find_reachable(from);
-        let group1 = reachable.len();
-        let group2 = tmp_graph.edges.len() - group1;
-        dbg!(group1, group2, group1 * group2);
+    fn part1(wiring: &Self::Input) -> Answer {
+        part1(wiring).into()
+    }
 
-        break;
+    fn part2(_wiring: &Self::Input) -> Answer {
+        // There is no puzzle for day 25 part 2.
+        Answer::Unsolved
     }
 }
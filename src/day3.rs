@@ -1,71 +1,49 @@
 use std::collections::HashMap;
 
+use crate::{Answer, AsPoint, Grid, ParseError, Pos, Solution};
+
 fn is_symbol(c: char) -> bool {
-    !c.is_digit(10) && c != '.'
+    !c.is_ascii_digit() && c != '.'
 }
 
 #[derive(Debug, Clone)]
-struct Grid {
-    chars: Vec<Vec<char>>,
+pub struct Schematic {
+    grid: Grid<char>,
     // For part 2, track numbers "attached" to each "*".
-    gears: HashMap<(usize, usize), Vec<u32>>,
+    gears: HashMap<Pos, Vec<u32>>,
 }
 
-impl Grid {
-    fn parse(s: &str) -> Self {
-        let chars = s.lines().map(|line| line.chars().collect()).collect();
-        let gears = HashMap::new();
-        Self { chars, gears }
-    }
-
+impl Schematic {
     // Returns the symbol and its position, if any.
-    fn get_adjacent_symbol(&self, y: usize, x: usize) -> Option<(char, usize, usize)> {
-        let y_from = if y > 0 { y - 1 } else { y };
-        let y_to = if (y + 1) < self.chars.len() { y + 1 } else { y };
-        let x_from = if x > 0 { x - 1 } else { x };
-        let x_to = if (x + 1) < self.chars[0].len() {
-            x + 1
-        } else {
-            x
-        };
-
-        for yy in y_from..=y_to {
-            for xx in x_from..=x_to {
-                let c = self.chars[yy][xx];
-                if is_symbol(c) {
-                    return Some((c, yy, xx));
-                }
-            }
-        }
-        None
+    fn get_adjacent_symbol(&self, pos: Pos) -> Option<(char, Pos)> {
+        pos.all_neighbors().find_map(|p| match self.grid.get(p) {
+            Some(&c) if is_symbol(c) => Some((c, p)),
+            _ => None,
+        })
     }
 
-    fn maybe_extract_number(&mut self, y: usize, mut x: usize) -> Option<u32> {
-        if !self.chars[y][x].is_digit(10) {
+    fn maybe_extract_number(&mut self, mut pos: Pos) -> Option<u32> {
+        if !self.grid[pos].is_ascii_digit() {
             return None;
         }
         let mut tmp = 0u32;
         let mut symbol_pos = None;
-        while x < self.chars[0].len() {
-            match self.chars[y][x].to_digit(10) {
-                Some(d) => {
-                    tmp = tmp * 10 + d;
-                    self.chars[y][x] = '.';
-                    if symbol_pos.is_none() {
-                        symbol_pos = self.get_adjacent_symbol(y, x);
-                    }
-                }
-                None => {
-                    break;
-                }
+        while let Some(d) = self.grid.get(pos).and_then(|c| c.to_digit(10)) {
+            tmp = tmp * 10 + d;
+            self.grid[pos] = '.';
+            if symbol_pos.is_none() {
+                symbol_pos = self.get_adjacent_symbol(pos);
             }
-            x += 1;
+            pos += &Pos::right();
         }
         match symbol_pos {
             None => None,
-            Some(('*', y, x)) => {
-                // dbg!(y, x);
-                self.gears.entry((y, x)).and_modify(|v| v.push(tmp)).or_insert(vec![tmp]);
+            Some(('*', pos)) => {
+                // dbg!(pos);
+                self.gears
+                    .entry(pos)
+                    .and_modify(|v| v.push(tmp))
+                    .or_insert(vec![tmp]);
                 // dbg!(&self.gears);
                 Some(tmp)
             }
@@ -75,11 +53,9 @@ impl Grid {
 
     fn sum_part_numbers(&mut self) -> u32 {
         let mut sum = 0u32;
-        for y in 0..self.chars.len() {
-            for x in 0..self.chars[0].len() {
-                if let Some(number) = self.maybe_extract_number(y, x) {
-                    sum += number;
-                }
+        for pos in self.grid.positions() {
+            if let Some(number) = self.maybe_extract_number(pos) {
+                sum += number;
             }
         }
         sum
@@ -97,9 +73,26 @@ impl Grid {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut grid = Grid::parse(&input);
-    dbg!(grid.sum_part_numbers());
-    dbg!(grid.sum_gear_ratios());
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        Ok(Schematic {
+            grid: input.parse()?,
+            gears: HashMap::new(),
+        })
+    }
+
+    fn part1(schematic: &Self::Input) -> Answer {
+        schematic.clone().sum_part_numbers().into()
+    }
+
+    fn part2(schematic: &Self::Input) -> Answer {
+        // Gears are collected while extracting the part numbers.
+        let mut schematic = schematic.clone();
+        schematic.sum_part_numbers();
+        schematic.sum_gear_ratios().into()
+    }
 }
@@ -1,29 +1,28 @@
 use std::collections::HashSet;
 
-#[derive(Debug)]
-struct Card {
+use crate::{parse_num, split_once, Answer, ParseError, Solution};
+
+#[derive(Debug, Clone)]
+pub struct Card {
     winning: HashSet<u32>,
     have: Vec<u32>,
     // for part 2
     count: u32,
 }
 
-fn parse_ints(s: &str) -> Vec<u32> {
-    s.trim()
-        .split_whitespace()
-        .map(|x| x.parse::<u32>().unwrap())
-        .collect()
+fn parse_ints(s: &str) -> Result<Vec<u32>, ParseError> {
+    s.split_whitespace().map(parse_num).collect()
 }
 
 impl Card {
-    fn parse(s: &str) -> Self {
-        let after_colon = s.split_once(':').unwrap().1;
-        let (w, h) = after_colon.split_once(" | ").unwrap();
-        Self {
-            winning: HashSet::from_iter(parse_ints(w).into_iter()),
-            have: parse_ints(h),
+    fn parse(s: &str) -> Result<Self, ParseError> {
+        let after_colon = split_once(s, ":")?.1;
+        let (w, h) = split_once(after_colon, " | ")?;
+        Ok(Self {
+            winning: HashSet::from_iter(parse_ints(w)?),
+            have: parse_ints(h)?,
             count: 1,
-        }
+        })
     }
 
     fn count_matches(&self) -> u32 {
@@ -55,9 +54,20 @@ fn part2(cards: &mut [Card]) -> u32 {
     cards.iter().map(|c| c.count).sum()
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut cards: Vec<Card> = input.lines().map(|l| Card::parse(l)).collect();
-    dbg!(part1(&cards));
-    dbg!(part2(&mut cards));
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(Card::parse).collect()
+    }
+
+    fn part1(cards: &Self::Input) -> Answer {
+        part1(cards).into()
+    }
+
+    fn part2(cards: &Self::Input) -> Answer {
+        part2(&mut cards.clone()).into()
+    }
 }
@@ -1,5 +1,9 @@
+use itertools::Itertools;
+
+use crate::{parse_num, Answer, ParseError, Solution};
+
 #[derive(Debug)]
-struct Race {
+pub struct Race {
     time: u64,
     distance: u64,
 }
@@ -25,21 +29,62 @@ fn calculate_ways_to_win_product(races: &[Race]) -> u64 {
     races.iter().map(|r| r.count_ways_to_win()).product::<u64>()
 }
 
-fn main() {
-    let sample_part1 = vec![Race::new(7, 9), Race::new(15, 40), Race::new(30, 200)];
-    dbg!(&calculate_ways_to_win_product(&sample_part1));
+// Parses "Time: ..." and "Distance: ..." lines into one race per column.
+fn parse_races(s: &str) -> Result<Vec<Race>, ParseError> {
+    let (times, distances) = s
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .skip(1)
+                .map(parse_num::<u64>)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .collect_tuple()
+        .ok_or_else(|| ParseError::new(s, "\"Time:\" and \"Distance:\" lines"))?;
+    if times.len() != distances.len() {
+        return Err(ParseError::new(s, "as many distances as times"));
+    }
+    let races: Vec<Race> = times
+        .into_iter()
+        .zip(distances)
+        .map(|(time, distance)| Race::new(time, distance))
+        .collect();
+    if concat_races(&races).is_none() {
+        return Err(ParseError::new(
+            s,
+            "races whose joined numbers fit in a u64",
+        ));
+    }
+    Ok(races)
+}
+
+// For part 2 the spaces between numbers are ignored, so all the races make up one long race.
+// None if there are no races or the numbers get too long.
+fn concat_races(races: &[Race]) -> Option<Race> {
+    let concat = |values: Vec<u64>| values.iter().join("").parse().ok();
+    Some(Race::new(
+        concat(races.iter().map(|r| r.time).collect())?,
+        concat(races.iter().map(|r| r.distance).collect())?,
+    ))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Race>;
 
-    let input_part1 = vec![
-        Race::new(41, 214),
-        Race::new(96, 1789),
-        Race::new(88, 1127),
-        Race::new(94, 1055),
-    ];
-    dbg!(&calculate_ways_to_win_product(&input_part1));
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_races(input)
+    }
 
-    let sample_part2 = vec![Race::new(71530, 940200)];
-    dbg!(&calculate_ways_to_win_product(&sample_part2));
+    fn part1(races: &Self::Input) -> Answer {
+        calculate_ways_to_win_product(races).into()
+    }
 
-    let input_part2 = vec![Race::new(41968894, 214178911271055)];
-    dbg!(&calculate_ways_to_win_product(&input_part2));
+    fn part2(races: &Self::Input) -> Answer {
+        let race = concat_races(races).expect("checked while parsing");
+        calculate_ways_to_win_product(&[race]).into()
+    }
 }
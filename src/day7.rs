@@ -2,6 +2,8 @@ use itertools::Itertools;
 use log::debug;
 use std::str::FromStr;
 
+use crate::{parse_num, split_once, Answer, ParseError, Solution};
+
 const ORDER_PART1: [char; 13] = [
     'A', 'K', 'Q', 'J', 'T', '9', '8', '7', '6', '5', '4', '3', '2',
 ];
@@ -32,7 +34,7 @@ enum HandType {
     FiveOfAKind,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct Hand {
     cards: [char; 5],
 }
@@ -116,39 +118,46 @@ impl std::fmt::Display for Hand {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 struct HandWithBid {
     hand: Hand,
     bid: u32,
 }
 
 impl FromStr for HandWithBid {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (hand_str, bid_str) = s.split_once(' ').unwrap();
+        let (hand_str, bid_str) = split_once(s, " ")?;
+        if hand_str.chars().any(|c| !ORDER_PART1.contains(&c)) {
+            return Err(ParseError::new(hand_str, "cards (AKQJT98765432)"));
+        }
         let hand = Hand {
-            cards: hand_str.chars().collect::<Vec<_>>().try_into().unwrap(),
+            cards: hand_str
+                .chars()
+                .collect::<Vec<_>>()
+                .try_into()
+                .map_err(|_| ParseError::new(hand_str, "5 cards"))?,
         };
-        let bid = bid_str.parse().unwrap();
+        let bid = parse_num(bid_str)?;
 
         Ok(Self { hand, bid })
     }
 }
 
-#[derive(Debug)]
-struct Problem {
+#[derive(Debug, Clone)]
+pub struct Problem {
     hands: Vec<HandWithBid>,
 }
 
 impl FromStr for Problem {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let hands = s
             .lines()
-            .map(|line| line.parse::<HandWithBid>().unwrap())
-            .collect();
+            .map(|line| line.parse::<HandWithBid>())
+            .collect::<Result<_, _>>()?;
         Ok(Self { hands })
     }
 }
@@ -163,29 +172,37 @@ impl Problem {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let mut problem: Problem = input.parse().unwrap();
-
-    // dbg!(&problem);
-    problem.hands.iter().for_each(|hwb| {
-        let hand_type1 = hwb.hand.hand_type(false);
-        let hand_type2 = hwb.hand.hand_type(true);
-        debug!(
-            "hand: {} type: {hand_type1:?} type w/ joker: {hand_type2:?}",
-            hwb.hand
-        );
-    });
-
-    // Part 1:
-    problem
-        .hands
-        .sort_by(|a, b| cmp_hands(&a.hand, &b.hand, false));
-    dbg!(problem.total_winnings());
-
-    // Part 2:
-    problem
-        .hands
-        .sort_by(|a, b| cmp_hands(&a.hand, &b.hand, true));
-    dbg!(problem.total_winnings());
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Problem;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let problem: Problem = input.parse()?;
+        problem.hands.iter().for_each(|hwb| {
+            let hand_type1 = hwb.hand.hand_type(false);
+            let hand_type2 = hwb.hand.hand_type(true);
+            debug!(
+                "hand: {} type: {hand_type1:?} type w/ joker: {hand_type2:?}",
+                hwb.hand
+            );
+        });
+        Ok(problem)
+    }
+
+    fn part1(problem: &Self::Input) -> Answer {
+        let mut problem = problem.clone();
+        problem
+            .hands
+            .sort_by(|a, b| cmp_hands(&a.hand, &b.hand, false));
+        problem.total_winnings().into()
+    }
+
+    fn part2(problem: &Self::Input) -> Answer {
+        let mut problem = problem.clone();
+        problem
+            .hands
+            .sort_by(|a, b| cmp_hands(&a.hand, &b.hand, true));
+        problem.total_winnings().into()
+    }
 }
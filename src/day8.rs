@@ -1,32 +1,71 @@
 use std::{collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
-use num::integer::lcm;
+use log::debug;
+
+use crate::{cycle, num, split_once, Answer, ParseError, Solution};
+
+// Steps at which a ghost is on a Z node: the listed ones before it enters its
+// cycle at `start`, then any step matching one of `hits` modulo `len`.
+#[derive(Debug)]
+struct Ghost {
+    prefix_hits: Vec<i64>,
+    start: i64,
+    hits: Vec<i64>,
+    len: i64,
+}
+
+impl Ghost {
+    fn on_z(&self, step: i64) -> bool {
+        if step < self.start {
+            self.prefix_hits.contains(&step)
+        } else {
+            self.hits
+                .iter()
+                .any(|hit| (step - hit).rem_euclid(self.len) == 0)
+        }
+    }
+}
 
 #[derive(Debug)]
-struct Network {
+pub struct Network {
     instructions: String,
     network: HashMap<String, (String, String)>,
 }
 
 impl FromStr for Network {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (i, n) = s.split_once("\n\n").unwrap();
+        let (i, n) = split_once(s, "\n\n")?;
+        if i.is_empty() {
+            return Err(ParseError::new(i, "L or R instructions"));
+        }
+        if let Some(pos) = i.find(|c| c != 'L' && c != 'R') {
+            let c = i[pos..].chars().next().unwrap();
+            return Err(ParseError::new(&i[pos..pos + c.len_utf8()], "L or R"));
+        }
         let instructions = i.to_string();
-        let network = n
+        let nodes = n
             .lines()
             .map(|line| {
-                let (from, to) = line.trim_matches(')').split_once(" = (").unwrap();
-                let tos = to
-                    .split(", ")
-                    .map(|x| x.to_string())
-                    .collect_tuple()
-                    .unwrap();
-                (from.to_string(), tos)
+                let (from, to) = split_once(line.trim_matches(')'), " = (")?;
+                Ok((from, split_once(to, ", ")?))
             })
+            .collect::<Result<Vec<_>, ParseError>>()?;
+        let network: HashMap<String, (String, String)> = nodes
+            .iter()
+            .map(|&(from, (left, right))| (from.to_string(), (left.to_string(), right.to_string())))
             .collect();
+        // Every step has to lead somewhere.
+        for (_, (left, right)) in &nodes {
+            if let Some(to) = [left, right]
+                .into_iter()
+                .find(|to| !network.contains_key(**to))
+            {
+                return Err(ParseError::new(to, "defined node"));
+            }
+        }
         Ok(Self {
             instructions,
             network,
@@ -35,46 +74,132 @@ impl FromStr for Network {
 }
 
 impl Network {
+    // Where following instruction `idx` from `loc` leads.
+    fn step(&self, loc: &str, idx: usize) -> &str {
+        let choices = self.network.get(loc).unwrap();
+        match self.instructions.as_bytes()[idx] {
+            b'L' => &choices.0,
+            b'R' => &choices.1,
+            _ => panic!(),
+        }
+    }
+
     fn find_path(&self, from: &str, to_pred: fn(&str) -> bool) -> usize {
         let mut loc = from;
         let mut steps = 0usize;
         while !to_pred(loc) {
-            let idx = steps % self.instructions.len();
+            loc = self.step(loc, steps % self.instructions.len());
             steps += 1;
-            // dbg!(loc);
-            let choices = self.network.get(loc).unwrap();
-            loc = match self.instructions.chars().nth(idx).unwrap() {
-                'L' => &choices.0,
-                'R' => &choices.1,
-                _ => panic!(),
-            };
         }
         steps
     }
 
-    fn part1(&self) -> usize {
-        self.find_path("AAA", |path| path == "ZZZ")
+    fn ghost(&self, from: &str) -> Ghost {
+        let len = self.instructions.len();
+        let found = cycle::detect((from, 0), |&(loc, idx)| {
+            (self.step(loc, idx), (idx + 1) % len)
+        });
+        let hits_in = |steps: std::ops::Range<usize>| {
+            steps
+                .filter(|&n| found.state_at(n).0.ends_with('Z'))
+                .map(|n| n as i64)
+                .collect()
+        };
+        Ghost {
+            prefix_hits: hits_in(0..found.start),
+            start: found.start as i64,
+            hits: hits_in(found.start..found.start + found.len),
+            len: found.len as i64,
+        }
+    }
+
+    // None if there is no AAA to start from, as in the part 2 example.
+    fn part1(&self) -> Option<usize> {
+        self.network
+            .contains_key("AAA")
+            .then(|| self.find_path("AAA", |path| path == "ZZZ"))
     }
 
-    fn part2(&self) -> usize {
+    fn part2(&self) -> i64 {
         let starts: Vec<_> = self
             .network
             .keys()
             .filter(|k| k.ends_with('A'))
             .cloned()
             .collect();
-        starts
+        let ghosts: Vec<_> = starts
             .iter()
-            .map(|s| dbg!(self.find_path(&s, |path| path.ends_with('Z'))))
-            .reduce(|acc, e| lcm(acc, e))
-            .unwrap()
+            .map(|s| {
+                let ghost = self.ghost(s);
+                debug!("{s}: {ghost:?}");
+                ghost
+            })
+            .collect();
+
+        // Until the last ghost enters its cycle, it can only be on one of the
+        // Z nodes it passes on the way.
+        let last = ghosts.iter().max_by_key(|g| g.start).expect("no ghosts");
+        if let Some(&step) = last
+            .prefix_hits
+            .iter()
+            .find(|&&step| ghosts.iter().all(|g| g.on_z(step)))
+        {
+            return step;
+        }
+
+        // After that, every ghost is on a Z node at one of its hits modulo its
+        // cycle length. Combine them one ghost at a time, keeping each
+        // distinct solution once.
+        let mut solutions = vec![(0, 1)];
+        for ghost in &ghosts {
+            solutions = solutions
+                .iter()
+                .cartesian_product(&ghost.hits)
+                .filter_map(|(&solution, &hit)| num::crt([solution, (hit, ghost.len)]))
+                .sorted()
+                .dedup()
+                .collect();
+        }
+        solutions
+            .into_iter()
+            .map(|(t, len)| {
+                let behind = (last.start - t).max(0);
+                t + (behind + len - 1) / len * len
+            })
+            .min()
+            .expect("ghosts never meet on Z nodes")
+    }
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Network;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(network: &Self::Input) -> Answer {
+        network.part1().map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(network: &Self::Input) -> Answer {
+        network.part2().into()
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let network: Network = input.parse().unwrap();
-    // dbg!(&network);
-    dbg!(network.part1());
-    dbg!(network.part2());
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_part2_prefix() {
+        // The first ghost only passes a Z node once, before settling in a loop.
+        let network: Network = "L\n\n11A = (11Z, XXX)\n11Z = (11B, XXX)\n11B = (11B, XXX)\n\
+            22A = (22Z, XXX)\n22Z = (22Z, XXX)\nXXX = (XXX, XXX)"
+            .parse()
+            .unwrap();
+        assert_eq!(network.part2(), 1);
+    }
 }
@@ -1,13 +1,15 @@
 use std::str::FromStr;
 
+use crate::{parse_num, Answer, ParseError, Solution};
+
 #[derive(Debug, Clone)]
-struct Seq(Vec<i64>);
+pub struct Seq(Vec<i64>);
 
 impl FromStr for Seq {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let values = s.split(' ').map(|x| x.parse::<i64>().unwrap()).collect();
+        let values = s.split(' ').map(parse_num).collect::<Result<_, _>>()?;
         Ok(Self(values))
     }
 }
@@ -25,9 +27,10 @@ impl Seq {
         Seq(out)
     }
 
+    // Stops early at a single value, which stays the same all along.
     fn deltas_until_zeros(&self) -> Vec<Seq> {
         let mut v = vec![self.clone()];
-        while !v.last().unwrap().all_zeros() {
+        while !v.last().unwrap().all_zeros() && v.last().unwrap().0.len() > 1 {
             v.push(v.last().unwrap().deltas());
         }
         v
@@ -48,12 +51,22 @@ impl Seq {
     }
 }
 
-fn main() {
-    let input = adv2023::read_input();
-    let seqs: Vec<Seq> = input.lines().map(|line| line.parse().unwrap()).collect();
-    // dbg!(&seqs);
-    let part1: i64 = seqs.iter().map(|s| s.extrapolate_last()).sum();
-    dbg!(&part1);
-    let part2: i64 = seqs.iter().map(|s| s.extrapolate_first()).sum();
-    dbg!(&part2);
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Seq>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        input.lines().map(|line| line.parse()).collect()
+    }
+
+    fn part1(seqs: &Self::Input) -> Answer {
+        let part1: i64 = seqs.iter().map(|s| s.extrapolate_last()).sum();
+        part1.into()
+    }
+
+    fn part2(seqs: &Self::Input) -> Answer {
+        let part2: i64 = seqs.iter().map(|s| s.extrapolate_first()).sum();
+        part2.into()
+    }
 }
@@ -0,0 +1,179 @@
+use std::{fmt, str::FromStr};
+
+use crate::{ParseError, Pos};
+
+/// Compass direction, with y going down so that `N` is `Pos::up()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    N,
+    NE,
+    E,
+    SE,
+    S,
+    SW,
+    W,
+    NW,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// Clockwise from north.
+    pub const ALL: [Direction; 8] = [N, NE, E, SE, S, SW, W, NW];
+    pub const ORTHOGONAL: [Direction; 4] = [N, E, S, W];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 8]
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self.index() + 2)
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self.index() + 6)
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::from_index(self.index() + 4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn is_horizontal(self) -> bool {
+        matches!(self, E | W)
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, N | S)
+    }
+
+    /// Direction after bouncing off a `/` or `\` mirror, None for other chars.
+    pub fn reflect(self, mirror: char) -> Option<Self> {
+        let Pos { y, x } = self.to_pos();
+        let pos = match mirror {
+            '/' => Pos::new(-x, -y),
+            '\\' => Pos::new(x, y),
+            _ => return None,
+        };
+        Self::from_pos(pos)
+    }
+
+    pub fn to_pos(self) -> Pos {
+        let (y, x) = match self {
+            N => (-1, 0),
+            NE => (-1, 1),
+            E => (0, 1),
+            SE => (1, 1),
+            S => (1, 0),
+            SW => (1, -1),
+            W => (0, -1),
+            NW => (-1, -1),
+        };
+        Pos::new(y, x)
+    }
+
+    /// Inverse of `to_pos`, None unless `pos` is a unit step.
+    pub fn from_pos(pos: Pos) -> Option<Self> {
+        Self::ALL.into_iter().find(|d| d.to_pos() == pos)
+    }
+
+    /// Parses an orthogonal direction from a compass letter ("NESW"),
+    /// a relative one ("URDL"), an arrow ("^>v<") or a digit ("0123",
+    /// clockwise from east).
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'N' | 'U' | '^' | '3' => Some(N),
+            'E' | 'R' | '>' | '0' => Some(E),
+            'S' | 'D' | 'v' | '1' => Some(S),
+            'W' | 'L' | '<' | '2' => Some(W),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    /// Anything `from_char` takes, or a diagonal such as "NE".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let d = match s {
+            "NE" => Some(NE),
+            "SE" => Some(SE),
+            "SW" => Some(SW),
+            "NW" => Some(NW),
+            _ => match s.chars().collect::<Vec<_>>()[..] {
+                [c] => Self::from_char(c),
+                _ => None,
+            },
+        };
+        d.ok_or_else(|| ParseError::new(s, "direction"))
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl From<Direction> for Pos {
+    fn from(dir: Direction) -> Self {
+        dir.to_pos()
+    }
+}
+
+impl std::ops::Add<Direction> for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Direction) -> Self::Output {
+        self + rhs.to_pos()
+    }
+}
+
+impl std::ops::AddAssign<Direction> for Pos {
+    fn add_assign(&mut self, rhs: Direction) {
+        *self += rhs.to_pos();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_direction() {
+        assert_eq!(E.turn_right(), S);
+        assert_eq!(N.turn_left(), W);
+        assert_eq!(NE.turn_left(), NW);
+        assert_eq!(W.reverse(), E);
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.reverse().to_pos(), -d.to_pos());
+            assert_eq!(Direction::from_pos(d.into()), Some(d));
+        }
+        assert_eq!(Direction::from_pos(Pos::new(0, 2)), None);
+
+        assert_eq!(E.reflect('/'), Some(N));
+        assert_eq!(N.reflect('/'), Some(E));
+        assert_eq!(E.reflect('\\'), Some(S));
+        assert_eq!(W.reflect('\\'), Some(N));
+        assert_eq!(NW.reflect('/'), Some(SE));
+        assert_eq!(E.reflect('|'), None);
+
+        assert_eq!(Pos::new(1, 1) + W, Pos::new(1, 0));
+        let parsed: Vec<Direction> = ["R", "v", "2", "N", "SE"]
+            .iter()
+            .map(|s| s.parse().unwrap())
+            .collect();
+        assert_eq!(parsed, vec![E, S, W, N, SE]);
+        assert!("x".parse::<Direction>().is_err());
+        assert!("NS".parse::<Direction>().is_err());
+    }
+}
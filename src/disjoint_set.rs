@@ -0,0 +1,215 @@
+/// Partition of the elements `0..len` into disjoint sets, merged with
+/// `union`. Sets are named by one of their members, their root.
+#[derive(Debug, Clone)]
+pub struct DisjointSet {
+    parent: Vec<usize>,
+    // Only up to date for roots.
+    size: Vec<usize>,
+    count: usize,
+}
+
+impl DisjointSet {
+    /// Every element in a set of its own.
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            count: len,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    /// Root of the set containing `x`.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parent[root] != root {
+            root = self.parent[root];
+        }
+        // Point everything on the way straight at the root.
+        let mut x = x;
+        while x != root {
+            x = std::mem::replace(&mut self.parent[x], root);
+        }
+        root
+    }
+
+    /// Merges the sets containing `a` and `b`, returning false if they
+    /// already were the same.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.count -= 1;
+        true
+    }
+
+    pub fn same(&mut self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    /// Size of the set containing `x`.
+    pub fn size(&mut self, x: usize) -> usize {
+        let root = self.find(x);
+        self.size[root]
+    }
+
+    /// Number of sets.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Members of each set, in order of their smallest member.
+    pub fn components(&mut self) -> Vec<Vec<usize>> {
+        let roots: Vec<usize> = (0..self.len()).map(|x| self.find(x)).collect();
+        group_by_root(&roots)
+    }
+}
+
+fn group_by_root(roots: &[usize]) -> Vec<Vec<usize>> {
+    let mut index = vec![usize::MAX; roots.len()];
+    let mut out: Vec<Vec<usize>> = vec![];
+    for (x, &root) in roots.iter().enumerate() {
+        if index[root] == usize::MAX {
+            index[root] = out.len();
+            out.push(vec![]);
+        }
+        out[index[root]].push(x);
+    }
+    out
+}
+
+/// Like `DisjointSet`, but unions can be undone, back to any checkpoint.
+/// Finding is O(log n) since paths can't be compressed.
+#[derive(Debug, Clone)]
+pub struct RollbackDisjointSet {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+    // Roots attached to another root, most recent last.
+    merges: Vec<usize>,
+}
+
+impl RollbackDisjointSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+            merges: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.parent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.parent.is_empty()
+    }
+
+    pub fn find(&self, mut x: usize) -> usize {
+        while self.parent[x] != x {
+            x = self.parent[x];
+        }
+        x
+    }
+
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        let (big, small) = if self.size[a] >= self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = big;
+        self.size[big] += self.size[small];
+        self.merges.push(small);
+        true
+    }
+
+    pub fn same(&self, a: usize, b: usize) -> bool {
+        self.find(a) == self.find(b)
+    }
+
+    pub fn size(&self, x: usize) -> usize {
+        self.size[self.find(x)]
+    }
+
+    pub fn count(&self) -> usize {
+        self.len() - self.merges.len()
+    }
+
+    pub fn components(&self) -> Vec<Vec<usize>> {
+        let roots: Vec<usize> = (0..self.len()).map(|x| self.find(x)).collect();
+        group_by_root(&roots)
+    }
+
+    /// Marker to roll back to, covering all unions so far.
+    pub fn checkpoint(&self) -> usize {
+        self.merges.len()
+    }
+
+    /// Undoes all unions since `checkpoint`.
+    pub fn rollback(&mut self, checkpoint: usize) {
+        while self.merges.len() > checkpoint {
+            let small = self.merges.pop().unwrap();
+            let big = self.parent[small];
+            self.size[big] -= self.size[small];
+            self.parent[small] = small;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disjoint_set() {
+        let mut set = DisjointSet::new(6);
+        assert!(set.union(0, 1));
+        assert!(set.union(2, 1));
+        assert!(!set.union(0, 2));
+        assert!(set.union(4, 5));
+        assert!(set.same(0, 2));
+        assert!(!set.same(0, 3));
+        assert_eq!((set.size(2), set.size(3)), (3, 1));
+        assert_eq!(set.count(), 3);
+        assert_eq!(set.components(), vec![vec![0, 1, 2], vec![3], vec![4, 5]]);
+
+        let mut set = RollbackDisjointSet::new(6);
+        set.union(0, 1);
+        set.union(4, 5);
+        let before = set.checkpoint();
+        set.union(1, 5);
+        set.union(3, 2);
+        assert_eq!(set.size(0), 4);
+        assert_eq!(set.count(), 2);
+        set.rollback(before);
+        assert!(!set.same(0, 5));
+        assert_eq!(set.size(0), 2);
+        assert_eq!(set.count(), 4);
+        assert_eq!(
+            set.components(),
+            vec![vec![0, 1], vec![2], vec![3], vec![4, 5]]
+        );
+        set.rollback(0);
+        assert_eq!(set.count(), 6);
+    }
+}
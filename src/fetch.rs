@@ -0,0 +1,148 @@
+use std::{env, fs, path::Path, time::Duration};
+
+pub const BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u16 = 2023;
+
+/// Environment variable holding the value of the site's `session` cookie.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+pub const USER_AGENT: &str = concat!(
+    "adv2023/",
+    env!("CARGO_PKG_VERSION"),
+    " (personal advent of code runner, via ureq)"
+);
+
+/// Talks to the puzzle site on behalf of the logged in user.
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(30))
+            .build();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            agent,
+        }
+    }
+
+    /// Client for the real site, with the session token from `AOC_SESSION` if set.
+    pub fn from_env() -> Self {
+        let session = env::var(SESSION_VAR).ok().filter(|s| !s.trim().is_empty());
+        Self::new(BASE_URL, session)
+    }
+
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    fn url(&self, day: u8, path: &str) -> String {
+        format!("{}/{YEAR}/day/{day}{path}", self.base_url)
+    }
+
+    fn cookie(&self) -> Result<String, String> {
+        let session = self
+            .session
+            .as_ref()
+            .ok_or_else(|| format!("{SESSION_VAR} is not set"))?;
+        Ok(format!("session={}", session.trim()))
+    }
+
+    /// Downloads the input of given day.
+    pub fn input(&self, day: u8) -> Result<String, String> {
+        let url = self.url(day, "/input");
+        log::debug!("fetching {url}");
+        let response = self
+            .agent
+            .get(&url)
+            .set("Cookie", &self.cookie()?)
+            .call()
+            .map_err(|e| http_error(&url, e))?;
+        response
+            .into_string()
+            .map_err(|e| format!("{url}: cannot read response: {e}"))
+    }
+
+    /// Posts an answer, returning the page the site responds with.
+    pub fn post_answer(&self, day: u8, part: u8, answer: &str) -> Result<String, String> {
+        let url = self.url(day, "/answer");
+        log::debug!("posting {answer} to {url}");
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(&[("level", &part.to_string()), ("answer", answer)])
+            .map_err(|e| http_error(&url, e))?;
+        response
+            .into_string()
+            .map_err(|e| format!("{url}: cannot read response: {e}"))
+    }
+
+    /// Returns the input from `path`, first downloading it there if it isn't there yet.
+    pub fn cached_input(&self, day: u8, path: &Path) -> Result<String, String> {
+        if path.exists() {
+            return fs::read_to_string(path)
+                .map_err(|e| format!("cannot read {}: {e}", path.display()));
+        }
+        let input = self.input(day)?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+        }
+        fs::write(path, &input).map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        Ok(input)
+    }
+}
+
+fn http_error(url: &str, e: ureq::Error) -> String {
+    match e {
+        ureq::Error::Status(status, response) => {
+            let body = response.into_string().unwrap_or_default();
+            format!("{url}: HTTP {status}: {}", body.trim())
+        }
+        ureq::Error::Transport(t) => format!("{url}: {t}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockServer;
+
+    #[test]
+    fn test_cached_input() {
+        let server = MockServer::start(vec![(200, "1abc2\n".to_string())]);
+        let client = Client::new(server.url(), Some("s3cret".to_string()));
+        let dir = env::temp_dir().join(format!("adv2023-fetch-{}", std::process::id()));
+        let path = dir.join("day1.txt");
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(client.cached_input(1, &path).unwrap(), "1abc2\n");
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1abc2\n");
+        // Served from the cache; the server would have nothing left to answer with.
+        assert_eq!(client.cached_input(1, &path).unwrap(), "1abc2\n");
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/1/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=s3cret\r\n"));
+        assert!(requests[0].contains(&format!("User-Agent: {USER_AGENT}\r\n")));
+        fs::remove_dir_all(&dir).unwrap();
+
+        let server = MockServer::start(vec![(404, "Not Found".to_string())]);
+        let client = Client::new(server.url(), Some("s3cret".to_string()));
+        let err = client.input(26).unwrap_err();
+        assert!(
+            err.ends_with("/2023/day/26/input: HTTP 404: Not Found"),
+            "{err}"
+        );
+
+        let client = Client::new(server.url(), None);
+        assert_eq!(client.input(1).unwrap_err(), "AOC_SESSION is not set");
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+use crate::{Graph, NodeId};
+
+/// Edges that split a graph in two when removed, with the two parts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    // Total weight of the cut edges.
+    pub value: usize,
+    pub edges: Vec<(NodeId, NodeId)>,
+    // With the source first, for a cut found by max flow.
+    pub sides: (Vec<NodeId>, Vec<NodeId>),
+}
+
+// Edge in the residual graph, along with the index of its reverse edge at `to`.
+struct Arc {
+    to: NodeId,
+    capacity: usize,
+    reverse: usize,
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    // The cut between `side` and everything else, for edges going out of it.
+    fn cut_around(&self, value: usize, side: &HashSet<NodeId>) -> Cut {
+        let edges = self
+            .all_edges()
+            .filter(|(from, e)| side.contains(from) != side.contains(&e.to))
+            .filter(|(from, _)| !self.is_directed() || side.contains(from))
+            .map(|(from, e)| (from, e.to))
+            .collect();
+        let (mut inside, mut outside): (Vec<_>, Vec<_>) =
+            (0..self.len()).partition(|id| side.contains(id));
+        inside.sort();
+        outside.sort();
+        Cut {
+            value,
+            edges,
+            sides: (inside, outside),
+        }
+    }
+
+    /// Maximum flow from `source` to `sink` with edge weights as capacities,
+    /// returned as the minimum cut separating them, which has the same value.
+    /// Uses Edmonds-Karp.
+    pub fn max_flow(&self, source: NodeId, sink: NodeId) -> Cut {
+        assert_ne!(source, sink);
+        let mut arcs: Vec<Vec<Arc>> = (0..self.len()).map(|_| vec![]).collect();
+        for (from, edge) in self.all_edges() {
+            let back = if self.is_directed() { 0 } else { edge.weight };
+            let (i, j) = (arcs[edge.to].len(), arcs[from].len());
+            arcs[from].push(Arc {
+                to: edge.to,
+                capacity: edge.weight,
+                reverse: i + usize::from(from == edge.to),
+            });
+            arcs[edge.to].push(Arc {
+                to: from,
+                capacity: back,
+                reverse: j,
+            });
+        }
+
+        let mut flow = 0;
+        loop {
+            // Shortest path with capacity left, as the arc used to get to each node.
+            let mut came_by: Vec<Option<(NodeId, usize)>> = vec![None; self.len()];
+            let mut queue = VecDeque::from([source]);
+            let mut seen = HashSet::from([source]);
+            while let Some(node) = queue.pop_front() {
+                for (i, arc) in arcs[node].iter().enumerate() {
+                    if arc.capacity > 0 && seen.insert(arc.to) {
+                        came_by[arc.to] = Some((node, i));
+                        queue.push_back(arc.to);
+                    }
+                }
+            }
+            if !seen.contains(&sink) {
+                return self.cut_around(flow, &seen);
+            }
+
+            let mut path = vec![];
+            let mut node = sink;
+            while let Some((prev, i)) = came_by[node] {
+                path.push((prev, i));
+                node = prev;
+            }
+            let pushed = path
+                .iter()
+                .map(|&(node, i)| arcs[node][i].capacity)
+                .min()
+                .unwrap();
+            for (node, i) in path {
+                arcs[node][i].capacity -= pushed;
+                let Arc { to, reverse, .. } = arcs[node][i];
+                arcs[to][reverse].capacity += pushed;
+            }
+            flow += pushed;
+        }
+    }
+
+    /// Lightest set of edges splitting the graph in two, ignoring edge
+    /// directions. Uses Stoer-Wagner. None if there are fewer than two nodes.
+    pub fn min_cut(&self) -> Option<Cut> {
+        if self.len() < 2 {
+            return None;
+        }
+        // Edge weights between groups of merged nodes, each group named after
+        // one of its members.
+        let mut weights: Vec<HashMap<NodeId, usize>> = vec![HashMap::new(); self.len()];
+        for (from, edge) in self.all_edges().filter(|(from, e)| *from != e.to) {
+            *weights[from].entry(edge.to).or_default() += edge.weight;
+            *weights[edge.to].entry(from).or_default() += edge.weight;
+        }
+        let mut members: Vec<Vec<NodeId>> = (0..self.len()).map(|id| vec![id]).collect();
+        let mut active: Vec<NodeId> = (0..self.len()).collect();
+        let mut best: Option<(usize, Vec<NodeId>)> = None;
+
+        while active.len() > 1 {
+            // Add groups one at a time, always the one most tightly connected
+            // to those added so far. The last one is cut off from the rest by
+            // exactly its connection to them.
+            let mut connection: HashMap<NodeId, usize> = active.iter().map(|&g| (g, 0)).collect();
+            let mut heap = BinaryHeap::from([(0, active[0])]);
+            let mut added = HashSet::new();
+            let mut order = vec![];
+            // Groups not connected to any added so far come last, in any order.
+            let next_unconnected = |added: &HashSet<NodeId>| {
+                active.iter().find(|g| !added.contains(g)).map(|&g| (0, g))
+            };
+            while let Some((w, g)) = heap.pop().or_else(|| next_unconnected(&added)) {
+                if added.contains(&g) || connection[&g] != w {
+                    continue;
+                }
+                added.insert(g);
+                order.push((g, w));
+                for (&h, &hw) in &weights[g] {
+                    if !added.contains(&h) {
+                        let c = connection.get_mut(&h).unwrap();
+                        *c += hw;
+                        heap.push((*c, h));
+                    }
+                }
+            }
+            let (last, cut) = order.pop().unwrap();
+            let (before, _) = *order.last().unwrap();
+            if best.as_ref().is_none_or(|(value, _)| cut < *value) {
+                best = Some((cut, members[last].clone()));
+            }
+
+            // Merge the last group into the one before it.
+            for (h, hw) in std::mem::take(&mut weights[last]) {
+                weights[h].remove(&last);
+                if h != before {
+                    *weights[before].entry(h).or_default() += hw;
+                    *weights[h].entry(before).or_default() += hw;
+                }
+            }
+            let moved = std::mem::take(&mut members[last]);
+            members[before].extend(moved);
+            active.retain(|&g| g != last);
+        }
+
+        let (value, side) = best.unwrap();
+        Some(
+            self.to_undirected()
+                .cut_around(value, &side.into_iter().collect()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flow() {
+        // Two triangles joined by a light edge and a heavier one.
+        let mut g = Graph::undirected();
+        let ids: Vec<_> = (0..6).map(|n| g.add_node(n)).collect();
+        for (a, b, w) in [
+            (0, 1, 3),
+            (1, 2, 3),
+            (2, 0, 3),
+            (3, 4, 3),
+            (4, 5, 3),
+            (5, 3, 3),
+        ] {
+            g.add_edge(ids[a], ids[b], w);
+        }
+        g.add_edge(ids[2], ids[3], 1);
+        g.add_edge(ids[0], ids[5], 2);
+
+        let cut = g.min_cut().unwrap();
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.edges.len(), 2);
+        let mut sides = [cut.sides.0, cut.sides.1];
+        sides.sort();
+        assert_eq!(sides, [vec![0, 1, 2], vec![3, 4, 5]]);
+
+        let cut = g.max_flow(ids[1], ids[4]);
+        assert_eq!(cut.value, 3);
+        assert_eq!(cut.sides, (vec![0, 1, 2], vec![3, 4, 5]));
+        assert_eq!(g.max_flow(ids[0], ids[1]).value, 3 + 3);
+
+        // Nothing to cut between parts that aren't connected.
+        assert!(g.remove_edge(ids[2], ids[3]));
+        assert!(g.remove_edge(ids[0], ids[5]));
+        let cut = g.min_cut().unwrap();
+        assert_eq!((cut.value, cut.edges.len()), (0, 0));
+
+        // Only one way round when directed.
+        let mut d = Graph::directed();
+        let [a, b, c] = ['a', 'b', 'c'].map(|n| d.add_node(n));
+        d.add_edge(a, b, 5);
+        d.add_edge(b, c, 2);
+        d.add_edge(c, a, 9);
+        let cut = d.max_flow(a, c);
+        assert_eq!(cut.value, 2);
+        assert_eq!(cut.edges, vec![(b, c)]);
+        assert_eq!(cut.sides, (vec![a, b], vec![c]));
+        assert_eq!(d.max_flow(c, b).value, 5);
+
+        assert_eq!(Graph::<u8>::undirected().min_cut(), None);
+    }
+}
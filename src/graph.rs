@@ -0,0 +1,317 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{self, Write},
+    hash::Hash,
+};
+
+use crate::search;
+
+/// Index of a node, in the order the nodes were added.
+pub type NodeId = usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub to: NodeId,
+    pub weight: usize,
+}
+
+/// Graph with weighted edges between nodes of type `N`, each stored once and
+/// referred to by its `NodeId`.
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    directed: bool,
+    nodes: Vec<N>,
+    ids: HashMap<N, NodeId>,
+    // Outgoing edges per node. Undirected edges are stored both ways.
+    edges: Vec<Vec<Edge>>,
+}
+
+impl<N: Hash + Eq + Clone> Graph<N> {
+    fn new(directed: bool) -> Self {
+        Self {
+            directed,
+            nodes: vec![],
+            ids: HashMap::new(),
+            edges: vec![],
+        }
+    }
+
+    pub fn directed() -> Self {
+        Self::new(true)
+    }
+
+    pub fn undirected() -> Self {
+        Self::new(false)
+    }
+
+    pub fn is_directed(&self) -> bool {
+        self.directed
+    }
+
+    /// Id of `node`, adding it if it's new.
+    pub fn add_node(&mut self, node: N) -> NodeId {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(vec![]);
+        id
+    }
+
+    pub fn id(&self, node: &N) -> Option<NodeId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, id: NodeId) -> &N {
+        &self.nodes[id]
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeId, &N)> {
+        self.nodes.iter().enumerate()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Adds an edge, going both ways if the graph is undirected. Edges may
+    /// repeat.
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId, weight: usize) {
+        self.edges[from].push(Edge { to, weight });
+        if !self.directed && from != to {
+            self.edges[to].push(Edge { to: from, weight });
+        }
+    }
+
+    /// Removes one edge from `from` to `to`, returning false if there is none.
+    pub fn remove_edge(&mut self, from: NodeId, to: NodeId) -> bool {
+        let Some(i) = self.edges[from].iter().position(|e| e.to == to) else {
+            return false;
+        };
+        self.edges[from].swap_remove(i);
+        if !self.directed && from != to {
+            let i = self.edges[to].iter().position(|e| e.to == from).unwrap();
+            self.edges[to].swap_remove(i);
+        }
+        true
+    }
+
+    pub fn has_edge(&self, from: NodeId, to: NodeId) -> bool {
+        self.edges[from].iter().any(|e| e.to == to)
+    }
+
+    /// Edges going out of `id`.
+    pub fn edges(&self, id: NodeId) -> &[Edge] {
+        &self.edges[id]
+    }
+
+    pub fn neighbors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        self.edges[id].iter().map(|e| e.to)
+    }
+
+    /// Every edge with the node it starts from. Undirected edges are listed
+    /// once, from the lower id.
+    pub fn all_edges(&self) -> impl Iterator<Item = (NodeId, &Edge)> {
+        let directed = self.directed;
+        self.edges
+            .iter()
+            .enumerate()
+            .flat_map(move |(from, edges)| {
+                edges
+                    .iter()
+                    .filter(move |e| directed || from <= e.to)
+                    .map(move |e| (from, e))
+            })
+    }
+
+    /// Same edges, with undirected ones turned into a pair of directed ones.
+    pub fn to_directed(&self) -> Self {
+        Self {
+            directed: true,
+            ..self.clone()
+        }
+    }
+
+    /// Same edges, with directed ones made to go both ways.
+    pub fn to_undirected(&self) -> Self {
+        if !self.directed {
+            return self.clone();
+        }
+        let mut graph = Self {
+            directed: false,
+            edges: vec![vec![]; self.len()],
+            ..self.clone()
+        };
+        for (from, edge) in self.all_edges() {
+            graph.add_edge(from, edge.to, edge.weight);
+        }
+        graph
+    }
+
+    // Follows `edge` out of `from` through nodes that aren't kept, returning
+    // where it ends and the total weight, unless it dead-ends.
+    fn walk_corridor(
+        &self,
+        from: NodeId,
+        edge: &Edge,
+        kept: &HashMap<NodeId, NodeId>,
+    ) -> Option<(NodeId, usize)> {
+        let (mut prev, mut cur, mut weight) = (from, edge.to, edge.weight);
+        while !kept.contains_key(&cur) {
+            // Never turn back the way we came.
+            let mut onward = self.edges[cur].iter().filter(|e| e.to != prev);
+            let next = onward.next()?;
+            assert!(onward.next().is_none(), "corridor branches at node {cur}");
+            (prev, cur, weight) = (cur, next.to, weight + next.weight);
+        }
+        Some((kept[&cur], weight))
+    }
+
+    /// Graph of the nodes for which `keep` is true, where each path between
+    /// two of them through other nodes becomes a single edge, weighing as much
+    /// as the whole path. Nodes that aren't kept must not branch, and ids of
+    /// the kept nodes stay in the same order.
+    pub fn contract(&self, keep: impl Fn(NodeId) -> bool) -> Self {
+        let mut graph = Self::new(self.directed);
+        let kept: HashMap<NodeId, NodeId> = (0..self.len())
+            .filter(|&id| keep(id))
+            .map(|id| (id, graph.add_node(self.nodes[id].clone())))
+            .collect();
+        for (&from, &new_from) in &kept {
+            for edge in &self.edges[from] {
+                // Undirected corridors are walked from both ends, so only
+                // add them one way each time.
+                if let Some((to, weight)) = self.walk_corridor(from, edge, &kept) {
+                    graph.edges[new_from].push(Edge { to, weight });
+                }
+            }
+        }
+        graph
+    }
+
+    /// Nodes ordered so that all edges go forward, None if there is a cycle.
+    /// Any undirected edge counts as a cycle.
+    pub fn topological_order(&self) -> Option<Vec<NodeId>> {
+        if !self.directed {
+            return self
+                .all_edges()
+                .next()
+                .is_none()
+                .then(|| (0..self.len()).collect());
+        }
+        let mut in_degree = vec![0; self.len()];
+        for (_, edge) in self.all_edges() {
+            in_degree[edge.to] += 1;
+        }
+        let mut ready: Vec<NodeId> = (0..self.len())
+            .rev()
+            .filter(|&id| in_degree[id] == 0)
+            .collect();
+        let mut order = vec![];
+        while let Some(id) = ready.pop() {
+            order.push(id);
+            for edge in &self.edges[id] {
+                in_degree[edge.to] -= 1;
+                if in_degree[edge.to] == 0 {
+                    ready.push(edge.to);
+                }
+            }
+        }
+        (order.len() == self.len()).then_some(order)
+    }
+
+    /// Groups of nodes connected to each other, ignoring edge directions.
+    pub fn components(&self) -> Vec<HashSet<NodeId>> {
+        let mut adjacent = self
+            .edges
+            .iter()
+            .map(|edges| edges.iter().map(|e| e.to).collect())
+            .collect::<Vec<Vec<_>>>();
+        if self.directed {
+            for (from, edge) in self.all_edges() {
+                adjacent[edge.to].push(from);
+            }
+        }
+        search::components(0..self.len(), |&id| adjacent[id].clone())
+    }
+}
+
+impl<N: Hash + Eq + Clone + fmt::Debug> Graph<N> {
+    /// Graphviz description, with nodes labeled by their debug output and
+    /// edges by their weight.
+    pub fn to_dot(&self) -> String {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        let mut out = String::new();
+        writeln!(out, "{kind} {{").unwrap();
+        for (id, node) in self.nodes() {
+            let label = format!("{node:?}").replace('"', "\\\"");
+            writeln!(out, "  {id} [label=\"{label}\"];").unwrap();
+        }
+        for (from, edge) in self.all_edges() {
+            writeln!(
+                out,
+                "  {from} {arrow} {} [label=\"{}\"];",
+                edge.to, edge.weight
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_graph() {
+        // a -> b -> c -> d -> e, with a shortcut from a to d.
+        let mut g = Graph::directed();
+        let [a, b, c, d, e] = ["a", "b", "c", "d", "e"].map(|n| g.add_node(n));
+        assert_eq!(g.add_node("c"), c);
+        g.add_edge(a, b, 1);
+        g.add_edge(b, c, 2);
+        g.add_edge(c, d, 3);
+        g.add_edge(d, e, 1);
+        g.add_edge(a, d, 10);
+        assert_eq!(g.topological_order(), Some(vec![a, b, c, d, e]));
+        assert_eq!(g.components().len(), 1);
+
+        // The way on from d dead-ends at e.
+        let contracted = g.contract(|id| id == a || id == d);
+        assert_eq!(contracted.len(), 2);
+        let mut edges: Vec<_> = contracted
+            .all_edges()
+            .map(|(f, e)| (f, e.to, e.weight))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![(0, 1, 6), (0, 1, 10)]);
+
+        assert!(g.remove_edge(d, e));
+        assert!(!g.remove_edge(d, e));
+        assert_eq!(g.components().len(), 2);
+        assert!(g.to_dot().contains("  2 -> 3 [label=\"3\"];\n"));
+
+        let mut u = g.to_undirected();
+        assert!(u.has_edge(d, a));
+        assert_eq!(u.topological_order(), None);
+        u.add_edge(e, e, 4);
+        assert_eq!(u.all_edges().count(), 5);
+        let dot = u.to_dot();
+        assert!(dot.starts_with("graph {\n  0 [label=\"\\\"a\\\"\"];\n"));
+        assert!(dot.contains("  4 -- 4 [label=\"4\"];\n"));
+        assert!(u.remove_edge(e, e));
+        assert_eq!(u.components().len(), 2);
+    }
+}
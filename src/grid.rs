@@ -0,0 +1,263 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use crate::{search, ParseError, Pos};
+
+/// Rectangular 2D grid, indexed by `Pos` with y going down.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    // Row after row.
+    cells: Vec<T>,
+    size: Pos,
+}
+
+impl<T> Grid<T> {
+    pub fn new(size: Pos, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        assert!(size.y >= 0 && size.x >= 0, "bad size: {size:?}");
+        Self {
+            cells: vec![fill; (size.y * size.x) as usize],
+            size,
+        }
+    }
+
+    /// Panics unless all rows are the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "ragged rows");
+        let size = Pos::new(rows.len(), width);
+        Self {
+            cells: rows.into_iter().flatten().collect(),
+            size,
+        }
+    }
+
+    /// Parses one cell per char, `f` returning None for chars that aren't `expected`.
+    pub fn parse(
+        s: &str,
+        f: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+        let lines: Vec<&str> = s.lines().collect();
+        for line in &lines {
+            let mut len = 0;
+            for (x, c) in line.char_indices() {
+                let cell =
+                    f(c).ok_or_else(|| ParseError::new(&line[x..x + c.len_utf8()], expected))?;
+                cells.push(cell);
+                len += 1;
+            }
+            match width {
+                None => width = Some(len),
+                Some(w) if w != len => {
+                    return Err(ParseError::new(line, format!("row of width {w}")));
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            cells,
+            size: Pos::new(lines.len(), width.unwrap_or(0)),
+        })
+    }
+
+    pub fn size(&self) -> Pos {
+        self.size
+    }
+
+    pub fn height(&self) -> usize {
+        self.size.y as usize
+    }
+
+    pub fn width(&self) -> usize {
+        self.size.x as usize
+    }
+
+    pub fn in_bounds(&self, pos: Pos) -> bool {
+        pos.check_bounds(&self.size)
+    }
+
+    fn offset(&self, pos: Pos) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| (pos.y * self.size.x + pos.x) as usize)
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width()..(y + 1) * self.width()]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height()).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        assert!(x < self.width());
+        self.cells.iter().skip(x).step_by(self.width())
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width()).map(|x| self.column(x))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let size = self.size;
+        (0..size.y).flat_map(move |y| (0..size.x).map(move |x| Pos { y, x }))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// Position of the first cell equal to `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Pos>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, v)| *v == value).map(|(pos, _)| pos)
+    }
+
+    /// Orthogonal neighbors that are within the grid.
+    pub fn neighbors(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        pos.orthogonal_neighbors()
+            .into_iter()
+            .filter(|p| self.in_bounds(*p))
+    }
+
+    fn open_neighbors<'a>(
+        &'a self,
+        passable: &'a impl Fn(&T) -> bool,
+    ) -> impl FnMut(&Pos) -> Vec<Pos> + 'a {
+        move |pos| {
+            self.neighbors(*pos)
+                .filter(|n| passable(&self[*n]))
+                .collect()
+        }
+    }
+
+    /// Steps from the nearest of `sources` to every cell reachable through
+    /// passable cells.
+    pub fn distances(
+        &self,
+        sources: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&T) -> bool,
+    ) -> HashMap<Pos, usize> {
+        search::bfs(sources, self.open_neighbors(&passable))
+    }
+
+    /// Passable cells reachable from `sources` through passable cells.
+    pub fn flood_fill(
+        &self,
+        sources: impl IntoIterator<Item = Pos>,
+        passable: impl Fn(&T) -> bool,
+    ) -> HashSet<Pos> {
+        search::flood_fill(sources, self.open_neighbors(&passable), |pos| {
+            self.get(*pos).is_some_and(&passable)
+        })
+    }
+
+    /// Groups of passable cells that are orthogonally connected.
+    pub fn components(&self, passable: impl Fn(&T) -> bool) -> Vec<HashSet<Pos>> {
+        let open = self.positions().filter(|pos| passable(&self[*pos]));
+        search::components(open, self.open_neighbors(&passable))
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            size: self.size,
+        }
+    }
+}
+
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} out of bounds of {:?}", self.size))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        let size = self.size;
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} out of bounds of {size:?}"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Grid::parse(s, Some, "any char")
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let mut grid: Grid<char> = "#.S\n..#\n".parse().unwrap();
+        assert_eq!(grid.size(), Pos::new(2, 3));
+        assert_eq!(grid[Pos::new(1, 2)], '#');
+        assert_eq!(grid.get(Pos::new(2, 0)), None);
+        assert_eq!(grid.get(Pos::new(0, -1)), None);
+        assert_eq!(grid.find(&'S'), Some(Pos::new(0, 2)));
+        assert_eq!(grid.row(1), &['.', '.', '#']);
+        assert_eq!(
+            grid.columns()
+                .map(|c| c.collect::<String>())
+                .collect::<Vec<_>>(),
+            vec!["#.", "..", "S#"]
+        );
+        assert_eq!(
+            grid.neighbors(Pos::new(0, 2)).collect::<Vec<_>>(),
+            vec![Pos::new(0, 1), Pos::new(1, 2)]
+        );
+        assert_eq!(grid.positions().count(), 6);
+
+        grid[Pos::new(0, 2)] = '.';
+        assert_eq!(grid.to_string(), "#..\n..#\n");
+        let walls = grid.map(|&c| c == '#');
+        assert_eq!(walls.iter().filter(|(_, &w)| w).count(), 2);
+
+        let input = "12\n3x\n";
+        let err = Grid::parse(input, |c| c.to_digit(10), "digit")
+            .unwrap_err()
+            .locate(17, input);
+        assert_eq!(err.location.unwrap().col, 2);
+        assert!("ab\nc\n".parse::<Grid<char>>().is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use std::collections::BTreeMap;
+
+use num::PrimInt;
+
+use crate::{Range, Ranges};
+
+/// Product of one set of values per dimension, with dimensions named by `K`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperRect<K, T> {
+    dims: BTreeMap<K, Ranges<T>>,
+}
+
+impl<K: Ord + Clone, T: PrimInt> HyperRect<K, T> {
+    /// Panics if `key` isn't one of the dimensions.
+    pub fn get(&self, key: &K) -> &Ranges<T> {
+        &self.dims[key]
+    }
+
+    pub fn dims(&self) -> impl Iterator<Item = (&K, &Ranges<T>)> {
+        self.dims.iter()
+    }
+
+    fn with(&self, key: &K, ranges: Ranges<T>) -> Self {
+        let mut new = self.clone();
+        *new.dims.get_mut(key).expect("unknown dimension") = ranges;
+        new
+    }
+
+    /// Splits into the parts with `key` below `at`, and at or above it.
+    pub fn split_at(&self, key: &K, at: T) -> (Self, Self) {
+        let ranges = self.get(key);
+        let below = ranges.intersect_one(&Range::new(T::min_value(), at));
+        let above = ranges.difference(&below);
+        (self.with(key, below), self.with(key, above))
+    }
+
+    /// Dimension by dimension intersection. Both must have the same dimensions.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let dims = self
+            .dims
+            .iter()
+            .map(|(k, r)| (k.clone(), r.intersection(other.get(k))))
+            .collect();
+        Self { dims }
+    }
+
+    /// Number of points within.
+    pub fn volume(&self) -> u128 {
+        self.dims.values().map(|r| r.len() as u128).product()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dims.values().any(|r| r.is_empty())
+    }
+
+    /// Parts of self outside of `other`, as disjoint boxes.
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        if self.intersect(other).is_empty() {
+            return vec![self.clone()];
+        }
+        // Peel off one dimension at a time: the part outside of `other` along
+        // it, then continue with the part inside.
+        let mut out = vec![];
+        let mut rest = self.clone();
+        for (k, r) in &other.dims {
+            let ranges = rest.get(k).clone();
+            let outside = rest.with(k, ranges.difference(r));
+            if !outside.is_empty() {
+                out.push(outside);
+            }
+            rest = rest.with(k, ranges.intersection(r));
+        }
+        out
+    }
+}
+
+impl<K: Ord, T> FromIterator<(K, Ranges<T>)> for HyperRect<K, T> {
+    fn from_iter<I: IntoIterator<Item = (K, Ranges<T>)>>(iter: I) -> Self {
+        Self {
+            dims: iter.into_iter().collect(),
+        }
+    }
+}
+
+/// Union of boxes, kept disjoint so that volumes simply add up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperRectSet<K, T> {
+    rects: Vec<HyperRect<K, T>>,
+}
+
+impl<K, T> Default for HyperRectSet<K, T> {
+    fn default() -> Self {
+        Self { rects: vec![] }
+    }
+}
+
+impl<K: Ord + Clone, T: PrimInt> HyperRectSet<K, T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the parts of `rect` that aren't in the set yet.
+    pub fn insert(&mut self, rect: &HyperRect<K, T>) {
+        let mut pieces = vec![rect.clone()];
+        for existing in &self.rects {
+            pieces = pieces.iter().flat_map(|p| p.subtract(existing)).collect();
+        }
+        self.rects
+            .extend(pieces.into_iter().filter(|p| !p.is_empty()));
+    }
+
+    pub fn remove(&mut self, rect: &HyperRect<K, T>) {
+        self.rects = self.rects.iter().flat_map(|r| r.subtract(rect)).collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut new = self.clone();
+        other.rects.iter().for_each(|r| new.insert(r));
+        new
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut new = self.clone();
+        other.rects.iter().for_each(|r| new.remove(r));
+        new
+    }
+
+    pub fn volume(&self) -> u128 {
+        self.rects.iter().map(|r| r.volume()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rects.is_empty()
+    }
+
+    /// Disjoint boxes making up the set.
+    pub fn rects(&self) -> &[HyperRect<K, T>] {
+        &self.rects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rect(x: (i64, i64), y: (i64, i64)) -> HyperRect<char, i64> {
+        [('x', Ranges::new(x.0, x.1)), ('y', Ranges::new(y.0, y.1))]
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_hyperrect() {
+        let a = rect((0, 10), (0, 10));
+        assert_eq!(a.volume(), 100);
+        let (below, above) = a.split_at(&'y', 3);
+        assert_eq!(below, rect((0, 10), (0, 3)));
+        assert_eq!(above, rect((0, 10), (3, 10)));
+        assert!(a.split_at(&'x', -5).0.is_empty());
+
+        let b = rect((5, 15), (5, 15));
+        assert_eq!(a.intersect(&b), rect((5, 10), (5, 10)));
+        let pieces = a.subtract(&b);
+        assert_eq!(pieces.iter().map(|p| p.volume()).sum::<u128>(), 75);
+        assert_eq!(a.subtract(&rect((20, 30), (0, 10))), vec![a.clone()]);
+        assert!(a.subtract(&rect((-1, 11), (-1, 11))).is_empty());
+
+        let mut set = HyperRectSet::new();
+        set.insert(&a);
+        set.insert(&b);
+        assert_eq!(set.volume(), 175);
+        set.insert(&rect((2, 3), (2, 3)));
+        assert_eq!(set.volume(), 175);
+        set.remove(&rect((0, 15), (9, 11)));
+        assert_eq!(set.volume(), 175 - 15 - 10);
+
+        let other: HyperRectSet<_, _> = HyperRectSet::new().union(&set);
+        assert_eq!(other.volume(), set.volume());
+        assert!(set.difference(&other).is_empty());
+    }
+}
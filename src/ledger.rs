@@ -0,0 +1,249 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    str::FromStr,
+};
+
+use crate::{submit::Verdict, Answer};
+
+/// Submissions are recorded here, next to `answers.toml`.
+pub const DEFAULT_PATH: &str = "guesses.tsv";
+
+/// What the site said about one submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+}
+
+impl Outcome {
+    /// None for verdicts that say nothing about the answer.
+    pub fn from_verdict(verdict: &Verdict) -> Option<Self> {
+        match verdict {
+            Verdict::Correct => Some(Outcome::Correct),
+            Verdict::TooHigh => Some(Outcome::TooHigh),
+            Verdict::TooLow => Some(Outcome::TooLow),
+            Verdict::Incorrect => Some(Outcome::Wrong),
+            Verdict::Wait(_) | Verdict::AlreadySolved => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Outcome::Correct => "correct",
+            Outcome::TooHigh => "too_high",
+            Outcome::TooLow => "too_low",
+            Outcome::Wrong => "wrong",
+        }
+    }
+}
+
+/// Everything known about the answer to one part.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub correct: Option<Answer>,
+    pub rejected: BTreeSet<String>,
+    // Exclusive bounds from "too low" and "too high" answers.
+    pub low: Option<i128>,
+    pub high: Option<i128>,
+    // All guesses in submission order, for saving.
+    guesses: Vec<(Answer, Outcome)>,
+}
+
+fn numeric(answer: &Answer) -> Option<i128> {
+    match answer {
+        Answer::Int(v) => Some(*v as i128),
+        Answer::U128(v) => i128::try_from(*v).ok(),
+        Answer::Str(_) | Answer::Unsolved => None,
+    }
+}
+
+impl Entry {
+    fn add(&mut self, answer: &Answer, outcome: Outcome) {
+        let value = numeric(answer);
+        match outcome {
+            Outcome::Correct => self.correct = Some(answer.clone()),
+            Outcome::TooHigh => self.high = self.high.into_iter().chain(value).min(),
+            Outcome::TooLow => self.low = self.low.into_iter().chain(value).max(),
+            Outcome::Wrong => {}
+        }
+        if outcome != Outcome::Correct {
+            self.rejected.insert(answer.to_string());
+        }
+        self.guesses.push((answer.clone(), outcome));
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(correct) = &self.correct {
+            return write!(f, "solved, {correct}");
+        }
+        match (self.low, self.high) {
+            (Some(low), Some(high)) => write!(f, "> {low} and < {high}")?,
+            (Some(low), None) => write!(f, "> {low}")?,
+            (None, Some(high)) => write!(f, "< {high}")?,
+            (None, None) => write!(f, "no bounds")?,
+        }
+        write!(f, ", {} rejected", self.rejected.len())
+    }
+}
+
+/// Why an answer won't be submitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    AlreadySolved(Answer),
+    AlreadyRejected,
+    // With the bound it's outside of.
+    TooHigh(i128),
+    TooLow(i128),
+}
+
+impl fmt::Display for Refusal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Refusal::AlreadySolved(answer) => write!(f, "already solved with {answer}"),
+            Refusal::AlreadyRejected => write!(f, "already rejected before"),
+            Refusal::TooHigh(high) => write!(f, "too high, {high} already was"),
+            Refusal::TooLow(low) => write!(f, "too low, {low} already was"),
+        }
+    }
+}
+
+/// Answers submitted so far, stored as tab separated lines of day, part, outcome and answer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Ledger(BTreeMap<(u8, u8), Entry>);
+
+impl Ledger {
+    pub fn get(&self, day: u8, part: u8) -> Option<&Entry> {
+        self.0.get(&(day, part))
+    }
+
+    pub fn record(&mut self, day: u8, part: u8, answer: &Answer, outcome: Outcome) {
+        self.0.entry((day, part)).or_default().add(answer, outcome);
+    }
+
+    /// Checks the answer against what is known, before submitting it.
+    pub fn check(&self, day: u8, part: u8, answer: &Answer) -> Result<(), Refusal> {
+        let Some(entry) = self.get(day, part) else {
+            return Ok(());
+        };
+        if let Some(correct) = &entry.correct {
+            return Err(Refusal::AlreadySolved(correct.clone()));
+        }
+        if entry.rejected.contains(&answer.to_string()) {
+            return Err(Refusal::AlreadyRejected);
+        }
+        if let Some(value) = numeric(answer) {
+            match (entry.low, entry.high) {
+                (_, Some(high)) if value >= high => return Err(Refusal::TooHigh(high)),
+                (Some(low), _) if value <= low => return Err(Refusal::TooLow(low)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (u8, u8, &Entry)> {
+        self.0
+            .iter()
+            .map(|(&(day, part), entry)| (day, part, entry))
+    }
+}
+
+fn parse_answer(s: &str) -> Answer {
+    if let Ok(v) = s.parse::<i64>() {
+        Answer::Int(v)
+    } else if let Ok(v) = s.parse::<u128>() {
+        Answer::U128(v)
+    } else {
+        Answer::Str(s.to_string())
+    }
+}
+
+impl FromStr for Ledger {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ledger = Ledger::default();
+        for (i, line) in s.lines().enumerate() {
+            let items: Vec<_> = line.split('\t').collect();
+            let [day, part, outcome, answer] = items[..] else {
+                return Err(format!("line {}: expected 4 fields: {line}", i + 1));
+            };
+            let day = day
+                .parse()
+                .map_err(|_| format!("line {}: bad day", i + 1))?;
+            let part = match part {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(format!("line {}: bad part", i + 1)),
+            };
+            let outcome = match outcome {
+                "correct" => Outcome::Correct,
+                "too_high" => Outcome::TooHigh,
+                "too_low" => Outcome::TooLow,
+                "wrong" => Outcome::Wrong,
+                _ => return Err(format!("line {}: bad outcome: {outcome}", i + 1)),
+            };
+            ledger.record(day, part, &parse_answer(answer), outcome);
+        }
+        Ok(ledger)
+    }
+}
+
+impl fmt::Display for Ledger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (day, part, entry) in self.entries() {
+            for (answer, outcome) in &entry.guesses {
+                writeln!(f, "{day}\t{part}\t{}\t{answer}", outcome.name())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledger() {
+        let mut ledger = Ledger::default();
+        let a = Answer::from;
+        ledger.record(21, 2, &a(590104708070800u64), Outcome::TooHigh);
+        ledger.record(21, 2, &a(590104708070000u64), Outcome::TooLow);
+        ledger.record(21, 2, &a(590104708070750u64), Outcome::TooHigh);
+        ledger.record(21, 2, &a(590104708070400u64), Outcome::Wrong);
+
+        let refused = |v: u64| ledger.check(21, 2, &a(v));
+        assert_eq!(
+            refused(590104708070900),
+            Err(Refusal::TooHigh(590104708070750))
+        );
+        assert_eq!(
+            refused(590104708069999),
+            Err(Refusal::TooLow(590104708070000))
+        );
+        assert_eq!(refused(590104708070400), Err(Refusal::AlreadyRejected));
+        assert_eq!(refused(590104708070703), Ok(()));
+        assert_eq!(ledger.check(11, 2, &a(1u64)), Ok(()));
+        assert_eq!(
+            ledger.get(21, 2).unwrap().to_string(),
+            "> 590104708070000 and < 590104708070750, 4 rejected"
+        );
+
+        ledger.record(21, 2, &a(590104708070703u64), Outcome::Correct);
+        assert_eq!(
+            ledger.check(21, 2, &a(590104708070703u64)),
+            Err(Refusal::AlreadySolved(a(590104708070703u64)))
+        );
+
+        let saved = ledger.to_string();
+        assert!(saved.starts_with("21\t2\ttoo_high\t590104708070800\n"));
+        assert_eq!(saved.parse::<Ledger>(), Ok(ledger));
+        assert!("21\t3\twrong\t1".parse::<Ledger>().is_err());
+    }
+}
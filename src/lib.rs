@@ -1,10 +1,63 @@
-use itertools::Itertools;
+pub use cuboid::Cuboid;
+pub use direction::Direction;
+pub use disjoint_set::{DisjointSet, RollbackDisjointSet};
+pub use flow::Cut;
+pub use graph::{Edge, Graph, NodeId};
+pub use grid::Grid;
+pub use hyperrect::{HyperRect, HyperRectSet};
+pub use point::{AsPoint, Point, Pos3};
 pub use range::{Range, Ranges};
 use std::env;
 
+pub mod answers;
+pub mod bench;
+mod cuboid;
+pub mod cycle;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+mod direction;
+mod disjoint_set;
+pub mod fetch;
+mod flow;
+mod graph;
+mod grid;
+mod hyperrect;
+pub mod ledger;
+pub mod linalg;
+#[cfg(test)]
+mod mock;
+pub mod num;
+mod point;
 mod range;
+pub mod runner;
+pub mod search;
+pub mod submit;
 
-pub fn read_input() -> String {
+/// Sets up logging (debug by default) and backtraces for the binaries.
+pub fn init_logging() {
     if env::var("RUST_BACKTRACE").is_err() {
         env::set_var("RUST_BACKTRACE", "1");
     }
@@ -12,9 +65,158 @@ pub fn read_input() -> String {
         env::set_var("RUST_LOG", "debug");
     }
     env_logger::init();
+}
+
+/// One day's puzzle: the input is parsed once and then handed to both parts.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Answer;
+    fn part2(input: &Self::Input) -> Answer;
+}
+
+/// Answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Answer {
+    Int(i64),
+    // For anything that doesn't fit in i64.
+    U128(u128),
+    Str(String),
+    Unsolved,
+}
+
+impl std::fmt::Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Int(v) => write!(f, "{v}"),
+            Answer::U128(v) => write!(f, "{v}"),
+            Answer::Str(s) => write!(f, "{s}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(v: $t) -> Self {
+                    match i64::try_from(v) {
+                        Ok(v) => Answer::Int(v),
+                        Err(_) => Answer::U128(v.try_into().expect("negative answer out of range")),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+answer_from_int!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Self {
+        Answer::Str(s.to_string())
+    }
+}
+
+/// Error from parsing puzzle input.
+///
+/// Parsers point it at the offending part of the `&str` they were given. As long as that is a
+/// slice of the whole input (as from `lines()`, `split()`, `trim()` etc), the runner can then
+/// `locate` it to a line and column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub expected: String,
+    // Address and length of the offending token.
+    addr: usize,
+    len: usize,
+    pub day: Option<u8>,
+    pub location: Option<Location>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    // Both 1-based.
+    pub line: usize,
+    pub col: usize,
+    pub line_text: String,
+}
+
+impl ParseError {
+    pub fn new(token: &str, expected: impl Into<String>) -> Self {
+        Self {
+            expected: expected.into(),
+            addr: token.as_ptr() as usize,
+            len: token.len(),
+            day: None,
+            location: None,
+        }
+    }
+
+    /// Error pointing just past the end of `s`, for when something is missing.
+    pub fn at_end(s: &str, expected: impl Into<String>) -> Self {
+        Self::new(&s[s.len()..], expected)
+    }
+
+    /// Fills in the day, and the location if the token came from `input`.
+    pub fn locate(mut self, day: u8, input: &str) -> Self {
+        self.day = Some(day);
+        let start = input.as_ptr() as usize;
+        if self.addr < start || self.addr + self.len > start + input.len() {
+            return self;
+        }
+        let offset = self.addr - start;
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        self.location = Some(Location {
+            line: input[..offset].matches('\n').count() + 1,
+            col: input[line_start..offset].chars().count() + 1,
+            line_text: input[line_start..line_end].to_string(),
+        });
+        self
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}: ")?;
+        }
+        let Some(loc) = &self.location else {
+            return write!(f, "parse error: expected {}", self.expected);
+        };
+        writeln!(
+            f,
+            "parse error at line {}, column {}: expected {}",
+            loc.line, loc.col, self.expected
+        )?;
+        writeln!(f, "  {}", loc.line_text)?;
+        let remaining = loc.line_text.chars().count().saturating_sub(loc.col - 1);
+        let width = self.len.min(remaining).max(1);
+        write!(f, "  {}{}", " ".repeat(loc.col - 1), "^".repeat(width))
+    }
+}
 
-    let path = std::env::args().nth(1).expect("pls provide input file");
-    std::fs::read_to_string(path).expect("read failed")
+impl std::error::Error for ParseError {}
+
+/// Parses a number, pointing the error at `s` if it isn't one.
+pub fn parse_num<T: std::str::FromStr>(s: &str) -> Result<T, ParseError> {
+    s.parse().map_err(|_| ParseError::new(s, "number"))
+}
+
+/// Like `str::split_once`, but with an error if `delim` isn't there.
+pub fn split_once<'a>(s: &'a str, delim: &str) -> Result<(&'a str, &'a str), ParseError> {
+    s.split_once(delim)
+        .ok_or_else(|| ParseError::at_end(s, format!("{delim:?}")))
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -53,10 +255,10 @@ impl Pos {
         self.x >= 0 && self.x < size.x && self.y >= 0 && self.y < size.y
     }
 
-    // pub fn wrap(&self, size: &Pos) -> Pos {
-    //     Pos(self.y % size.y, self.x % size.x)
-    // }
-    //
+    /// Wraps around into `0..size`, as on a grid that repeats forever.
+    pub fn wrap(&self, size: &Pos) -> Pos {
+        self % size
+    }
 
     pub fn orthogonal_neighbors(&self) -> [Pos; 4] {
         [
@@ -67,101 +269,43 @@ impl Pos {
         ]
     }
 
-    pub fn ccw(&self) -> Self {
-        // (0, 1) -> (-1, 0)
-        // (-1, 0) -> (0, -1)
-        // (0, -1) -> (1, 0)
-        // (1, 0) -> (0, 1)
-        let (y, x) = match (self.y, self.x) {
-            (0, t) if t != 0 => (-t, 0),
-            (t, 0) if t != 0 => (0, t),
-            _ => panic!("not a single dir before: {:?}", self),
-        };
-        Pos::new(y, x)
-    }
-
-    pub fn cw(&self) -> Self {
-        // (0, 1) -> (1, 0)
-        // (1, 0) -> (0, -1)
-        // (0, -1) -> (-1, 0)
-        // (-1, 0) -> (0, 1)
-        let (y, x) = match (self.y, self.x) {
-            (0, t) if t != 0 => (t, 0),
-            (t, 0) if t != 0 => (0, -t),
-            _ => panic!("not a single dir before: {:?}", self),
-        };
-        Pos::new(y, x)
-    }
-
     pub fn opposite(&self) -> Self {
-        Pos::new(-self.y, -self.x)
-    }
-}
-
-impl std::ops::Add for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: Pos) -> Self::Output {
-        Pos {
-            y: self.y + rhs.y,
-            x: self.x + rhs.x,
-        }
-    }
-}
-
-impl std::ops::Add for &Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: &Pos) -> Self::Output {
-        Pos::add(*self, *rhs)
-    }
-}
-
-impl std::ops::Add<&Pos> for Pos {
-    type Output = Pos;
-
-    fn add(self, rhs: &Pos) -> Self::Output {
-        Pos::add(self, *rhs)
+        -*self
     }
 }
 
-impl std::ops::AddAssign<&Pos> for Pos {
-    fn add_assign(&mut self, rhs: &Pos) {
-        self.x += rhs.x;
-        self.y += rhs.y;
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
-pub struct Pos3 {
-    pub x: isize,
-    pub y: isize,
-    pub z: isize,
-}
+    #[test]
+    fn test_parse_error() {
+        let input = "1 2\n3 x 5\n";
+        let line = input.lines().nth(1).unwrap();
+        let err = line
+            .split(' ')
+            .map(parse_num::<u32>)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err()
+            .locate(9, input);
+        assert_eq!(
+            err.location,
+            Some(Location {
+                line: 2,
+                col: 3,
+                line_text: "3 x 5".to_string()
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "day 9: parse error at line 2, column 3: expected number\n  3 x 5\n    ^"
+        );
 
-impl Pos3 {
-    pub fn new<T>(x: T, y: T, z: T) -> Self
-    where
-        T: TryInto<isize>,
-    {
-        let x = x.try_into().unwrap_or_else(|_| panic!());
-        let y = y.try_into().unwrap_or_else(|_| panic!());
-        let z = z.try_into().unwrap_or_else(|_| panic!());
-        Self { x, y, z }
-    }
-
-    pub fn below(&self) -> Self {
-        Self {
-            x: self.x,
-            y: self.y,
-            z: self.z - 1,
-        }
-    }
-}
+        let err = split_once(line, ": ").unwrap_err().locate(9, input);
+        assert_eq!(err.location.unwrap().col, 6);
 
-impl FromIterator<isize> for Pos3 {
-    fn from_iter<T: IntoIterator<Item = isize>>(iter: T) -> Self {
-        let (x, y, z) = iter.into_iter().collect_tuple().expect("expected 3 items");
-        Self { x, y, z }
+        // Tokens that aren't from the input can't be located.
+        let err = parse_num::<u32>("x").unwrap_err().locate(9, input);
+        assert_eq!(err.to_string(), "day 9: parse error: expected number");
     }
 }
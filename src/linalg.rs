@@ -0,0 +1,201 @@
+use std::ops::Index;
+
+use ::num::{rational::Ratio, Num};
+
+/// Exact fraction, enough for most puzzle sized systems. For larger ones
+/// `num::BigRational` works the same way.
+pub type Rational = Ratio<i128>;
+
+/// Matrix of exact numbers, like `Rational`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: Vec<Vec<T>>,
+}
+
+/// Outcome of solving a system of linear equations.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solved<T> {
+    Unique(Vec<T>),
+    // Infinitely many solutions, of which `example` is the one with all
+    // free variables zero. `rank` is the number of independent equations.
+    Underdetermined { example: Vec<T>, rank: usize },
+    Inconsistent,
+}
+
+impl<T: Clone + Num> Matrix<T> {
+    /// Panics unless all rows have the same length.
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        assert!(
+            rows.iter().all(|r| r.len() == rows[0].len()),
+            "rows of different lengths"
+        );
+        Self { rows }
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |r| r.len())
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.rows[i]
+    }
+
+    /// Brings the matrix into reduced row echelon form, returning the column
+    /// of the leading one in each row that isn't all zeros.
+    pub fn row_reduce(&mut self) -> Vec<usize> {
+        let mut pivots = vec![];
+        for col in 0..self.width() {
+            let r = pivots.len();
+            let Some(p) = (r..self.height()).find(|&i| !self.rows[i][col].is_zero()) else {
+                continue;
+            };
+            self.rows.swap(r, p);
+            let pivot = self.rows[r][col].clone();
+            for x in &mut self.rows[r] {
+                *x = x.clone() / pivot.clone();
+            }
+            let pivot_row = self.rows[r].clone();
+            for (i, row) in self.rows.iter_mut().enumerate() {
+                let factor = row[col].clone();
+                if i == r || factor.is_zero() {
+                    continue;
+                }
+                for (x, p) in row.iter_mut().zip(&pivot_row) {
+                    *x = x.clone() - factor.clone() * p.clone();
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// Number of linearly independent rows.
+    pub fn rank(&self) -> usize {
+        self.clone().row_reduce().len()
+    }
+
+    /// Panics unless the matrix is square.
+    pub fn determinant(&self) -> T {
+        assert_eq!(self.height(), self.width(), "not a square matrix");
+        let mut m = self.clone();
+        let mut det = T::one();
+        for col in 0..m.width() {
+            let Some(p) = (col..m.height()).find(|&i| !m.rows[i][col].is_zero()) else {
+                return T::zero();
+            };
+            if p != col {
+                m.rows.swap(p, col);
+                det = T::zero() - det;
+            }
+            let pivot_row = m.rows[col].clone();
+            let pivot = pivot_row[col].clone();
+            det = det * pivot.clone();
+            for row in &mut m.rows[col + 1..] {
+                let factor = row[col].clone() / pivot.clone();
+                for (x, p) in row.iter_mut().zip(&pivot_row) {
+                    *x = x.clone() - factor.clone() * p.clone();
+                }
+            }
+        }
+        det
+    }
+
+    /// Solves `self * x = b`.
+    pub fn solve(&self, b: &[T]) -> Solved<T> {
+        assert_eq!(self.height(), b.len(), "one value per equation");
+        let width = self.width();
+        let augmented = self
+            .rows
+            .iter()
+            .zip(b)
+            .map(|(row, v)| row.iter().chain([v]).cloned().collect())
+            .collect();
+        let mut m = Self::new(augmented);
+        let pivots = m.row_reduce();
+        if pivots.last() == Some(&width) {
+            return Solved::Inconsistent;
+        }
+        let mut x = vec![T::zero(); width];
+        for (row, &col) in pivots.iter().enumerate() {
+            x[col] = m.rows[row][width].clone();
+        }
+        if pivots.len() == width {
+            Solved::Unique(x)
+        } else {
+            Solved::Underdetermined {
+                example: x,
+                rank: pivots.len(),
+            }
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    /// Indexed by (row, column).
+    fn index(&self, (row, col): (usize, usize)) -> &Self::Output {
+        &self.rows[row][col]
+    }
+}
+
+impl<T: Clone + Num> From<Vec<Vec<i128>>> for Matrix<T>
+where
+    T: From<i128>,
+{
+    fn from(rows: Vec<Vec<i128>>) -> Self {
+        Self::new(
+            rows.into_iter()
+                .map(|r| r.into_iter().map(T::from).collect())
+                .collect(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ints(values: &[i128]) -> Vec<Rational> {
+        values.iter().map(|&v| Rational::from(v)).collect()
+    }
+
+    #[test]
+    fn test_linalg() {
+        // x + 2y = 5, 3x - y = 1.
+        let a: Matrix<Rational> = vec![vec![1, 2], vec![3, -1]].into();
+        assert_eq!(a.determinant(), Rational::from(-7));
+        assert_eq!(a.rank(), 2);
+        assert_eq!(a.solve(&ints(&[5, 1])), Solved::Unique(ints(&[1, 2])));
+        assert_eq!(
+            a.solve(&ints(&[1, 0])),
+            Solved::Unique(vec![Rational::new(1, 7), Rational::new(3, 7)])
+        );
+
+        // The second equation is twice the first.
+        let b: Matrix<Rational> = vec![vec![1, 1, 1], vec![2, 2, 2], vec![0, 1, -1]].into();
+        assert_eq!(b.rank(), 2);
+        assert_eq!(b.determinant(), Rational::from(0));
+        assert_eq!(
+            b.solve(&ints(&[3, 6, 1])),
+            Solved::Underdetermined {
+                example: ints(&[2, 1, 0]),
+                rank: 2
+            }
+        );
+        assert_eq!(b.solve(&ints(&[3, 5, 1])), Solved::Inconsistent);
+
+        let mut c = b.clone();
+        assert_eq!(c.row_reduce(), vec![0, 1]);
+        assert_eq!(c.row(0), &ints(&[1, 0, 2])[..]);
+        assert_eq!(c[(2, 2)], Rational::from(0));
+
+        // Swapping rows flips the sign.
+        let d: Matrix<Rational> = vec![vec![0, 2, 0], vec![3, 0, 0], vec![0, 0, 4]].into();
+        assert_eq!(d.determinant(), Rational::from(-24));
+    }
+}
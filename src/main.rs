@@ -0,0 +1,391 @@
+use std::{path::Path, process::exit, time::SystemTime};
+
+use adv2023::{
+    answers::{self, Answers, CheckStatus},
+    bench::{self, Baseline},
+    fetch::{self, Client},
+    ledger::{self, Ledger, Outcome},
+    runner::{self, DayResult, OutputFormat},
+    submit::{self, Throttle, Verdict},
+    Answer, ParseError,
+};
+
+const USAGE: &str = "usage:
+  aoc run <day|all> [--part <1|2>] [--example] [--format <plain|json|tsv>] [--check [--answers <file>]] [input file]
+  aoc bench <day|all> [--part <1|2>] [--example] [--runs <n>] [--save <file>] [--compare <file>] [input file]
+  aoc fetch <day|all>
+  aoc submit <day> <1|2> [input file]
+  aoc status <day|all>
+
+Missing input/dayN.txt files are downloaded when AOC_SESSION is set to the site's session cookie.";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    Run,
+    Bench,
+    Fetch,
+    Submit,
+    Status,
+}
+
+#[derive(Debug)]
+struct Args {
+    command: Command,
+    // None means all days.
+    day: Option<u8>,
+    parts: Vec<u8>,
+    input: Option<String>,
+    // Use examples/ instead of input/.
+    example: bool,
+    format: OutputFormat,
+    // Compare against known answers instead of printing them.
+    check: bool,
+    answers: Option<String>,
+    // For bench.
+    runs: usize,
+    save: Option<String>,
+    compare: Option<String>,
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("error: {msg}");
+    eprintln!("{USAGE}");
+    exit(2);
+}
+
+fn parse_args(args: &[String]) -> Args {
+    let mut it = args.iter();
+    let command = match it.next().map(|s| s.as_str()) {
+        Some("run") => Command::Run,
+        Some("bench") => Command::Bench,
+        Some("fetch") => Command::Fetch,
+        Some("submit") => Command::Submit,
+        Some("status") => Command::Status,
+        Some(cmd) => usage_error(&format!("unknown command: {cmd}")),
+        None => usage_error("missing command"),
+    };
+    let day = match it.next().map(|s| s.as_str()) {
+        Some("all") => None,
+        Some(d) => match d.parse::<u8>() {
+            Ok(d) if runner::get(d).is_some() => Some(d),
+            _ => usage_error(&format!("bad day: {d}")),
+        },
+        None => usage_error("missing day"),
+    };
+    let mut parts = vec![1, 2];
+    if command == Command::Submit {
+        if day.is_none() {
+            usage_error("submit takes a single day");
+        }
+        parts = match it.next().map(|s| s.as_str()) {
+            Some("1") => vec![1],
+            Some("2") => vec![2],
+            _ => usage_error("submit takes part 1 or 2"),
+        };
+    }
+    let mut input = None;
+    let mut format = OutputFormat::Plain;
+    let mut example = false;
+    let mut check = false;
+    let mut answers = None;
+    let mut runs = 10;
+    let mut save = None;
+    let mut compare = None;
+    while let Some(arg) = it.next() {
+        let mut value = || {
+            it.next()
+                .unwrap_or_else(|| usage_error(&format!("{arg} takes a value")))
+                .clone()
+        };
+        match arg.as_str() {
+            "--part" => {
+                parts = match value().as_str() {
+                    "1" => vec![1],
+                    "2" => vec![2],
+                    _ => usage_error("--part takes 1 or 2"),
+                }
+            }
+            "--format" => format = value().parse().unwrap_or_else(|e: String| usage_error(&e)),
+            "--example" => example = true,
+            "--check" => check = true,
+            "--answers" => answers = Some(value()),
+            "--runs" => {
+                runs = match value().parse() {
+                    Ok(n) if n > 0 => n,
+                    _ => usage_error("--runs takes a positive number"),
+                }
+            }
+            "--save" => save = Some(value()),
+            "--compare" => compare = Some(value()),
+            s if s.starts_with("--") => usage_error(&format!("unknown flag: {s}")),
+            s if input.is_none() => input = Some(s.to_string()),
+            s => usage_error(&format!("unexpected argument: {s}")),
+        }
+    }
+    if matches!(command, Command::Fetch | Command::Status) && input.is_some() {
+        usage_error("fetch and status take only a day");
+    }
+    if example && !matches!(command, Command::Run | Command::Bench) {
+        usage_error("--example only works with run and bench");
+    }
+    if example && input.is_some() {
+        usage_error("--example and an input file don't go together");
+    }
+    if day.is_none() && input.is_some() {
+        usage_error("input file can only be given for a single day");
+    }
+    Args {
+        command,
+        day,
+        parts,
+        input,
+        example,
+        format,
+        check,
+        answers,
+        runs,
+        save,
+        compare,
+    }
+}
+
+fn days(args: &Args) -> Vec<u8> {
+    match args.day {
+        Some(day) => vec![day],
+        None => (1..=runner::DAYS.len() as u8).collect(),
+    }
+}
+
+/// Reads inputs of requested days, with the parts to run on each.
+///
+/// Missing inputs are downloaded if possible, otherwise skipped when running all days.
+fn read_inputs(args: &Args) -> Vec<(u8, Vec<u8>, String)> {
+    if let Some(path) = &args.input {
+        return vec![(args.day.unwrap(), args.parts.clone(), read_file(path))];
+    }
+    if args.example {
+        return read_examples(args);
+    }
+    let client = Client::from_env();
+    let mut inputs = vec![];
+    for day in days(args) {
+        let path = runner::input_path(day);
+        if args.day.is_none() && !client.has_session() && !Path::new(&path).exists() {
+            eprintln!("day {day}: skipping, no {path}");
+            continue;
+        }
+        let input = client
+            .cached_input(day, Path::new(&path))
+            .unwrap_or_else(|e| fetch_error(day, &e));
+        inputs.push((day, args.parts.clone(), input));
+    }
+    inputs
+}
+
+/// Reads the examples of requested days; parts with their own example are run separately.
+fn read_examples(args: &Args) -> Vec<(u8, Vec<u8>, String)> {
+    let mut inputs = vec![];
+    for day in days(args) {
+        let mut by_path: Vec<(String, Vec<u8>)> = vec![];
+        for &part in &args.parts {
+            let path = runner::example_path(day, part);
+            match by_path.iter_mut().find(|(p, _)| *p == path) {
+                Some((_, parts)) => parts.push(part),
+                None => by_path.push((path, vec![part])),
+            }
+        }
+        for (path, parts) in by_path {
+            if args.day.is_none() && !Path::new(&path).exists() {
+                eprintln!("day {day}: skipping, no {path}");
+                continue;
+            }
+            inputs.push((day, parts, read_file(&path)));
+        }
+    }
+    inputs
+}
+
+fn fetch_error(day: u8, e: &str) -> ! {
+    eprintln!("day {day}: {e}");
+    exit(1);
+}
+
+fn parse_error(e: &ParseError) -> ! {
+    eprintln!("{e}");
+    exit(1);
+}
+
+fn read_file(path: &str) -> String {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|e| usage_error(&format!("cannot read {path}: {e}")))
+}
+
+fn parse_file<T: std::str::FromStr<Err = String>>(path: &str) -> T {
+    read_file(path)
+        .parse()
+        .unwrap_or_else(|e| usage_error(&format!("bad file {path}: {e}")))
+}
+
+fn print_result(result: &DayResult, format: OutputFormat) {
+    eprintln!("day {}: parse took {:?}", result.day, result.parse_time);
+    for part in &result.parts {
+        eprintln!("day {} part {} took {:?}", result.day, part.part, part.time);
+    }
+    for line in result.format(format) {
+        println!("{line}");
+    }
+}
+
+fn run(args: &Args) {
+    let answers: Option<Answers> = args.check.then(|| {
+        let default = match args.example {
+            true => runner::EXAMPLE_ANSWERS_PATH,
+            false => answers::DEFAULT_PATH,
+        };
+        parse_file(args.answers.as_deref().unwrap_or(default))
+    });
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    if args.format == OutputFormat::Tsv && answers.is_none() {
+        println!("{}", runner::TSV_HEADER);
+    }
+    for (day, parts, input) in read_inputs(args) {
+        let result = runner::get(day)
+            .unwrap()
+            .run(day, &input, &parts)
+            .unwrap_or_else(|e| parse_error(&e));
+        let Some(answers) = &answers else {
+            print_result(&result, args.format);
+            continue;
+        };
+        for check in answers.check(&result) {
+            println!("{check}");
+            match check.status {
+                CheckStatus::Pass => passed += 1,
+                CheckStatus::Fail(_) => failed += 1,
+                CheckStatus::Missing => missing += 1,
+            }
+        }
+    }
+    if answers.is_some() {
+        println!("{passed} passed, {failed} failed, {missing} missing");
+        if failed > 0 {
+            exit(1);
+        }
+    }
+}
+
+fn bench(args: &Args) {
+    let compare: Option<Baseline> = args.compare.as_deref().map(parse_file);
+    let mut baseline = Baseline::default();
+    for (day, parts, input) in read_inputs(args) {
+        let solution = runner::get(day).unwrap();
+        let result = bench::bench(solution, day, &input, &parts, args.runs)
+            .unwrap_or_else(|e| parse_error(&e));
+        for (phase, stats) in &result.phases {
+            let base = compare.as_ref().and_then(|c| c.get(day, phase));
+            println!("{}", bench::format_phase(day, phase, stats, base));
+        }
+        baseline.add(&result);
+    }
+    if let Some(path) = &args.save {
+        std::fs::write(path, baseline.to_string())
+            .unwrap_or_else(|e| usage_error(&format!("cannot write {path}: {e}")));
+    }
+}
+
+fn fetch(args: &Args) {
+    let client = Client::from_env();
+    if !client.has_session() {
+        usage_error(&format!("{} is not set", fetch::SESSION_VAR));
+    }
+    for day in days(args) {
+        let path = runner::input_path(day);
+        if Path::new(&path).exists() {
+            println!("day {day}: {path} already cached");
+            continue;
+        }
+        client
+            .cached_input(day, Path::new(&path))
+            .unwrap_or_else(|e| fetch_error(day, &e));
+        println!("day {day}: saved {path}");
+    }
+}
+
+fn submit(args: &Args) {
+    let (day, part) = (args.day.unwrap(), args.parts[0]);
+    let client = Client::from_env();
+    if !client.has_session() {
+        usage_error(&format!("{} is not set", fetch::SESSION_VAR));
+    }
+    let throttle = Throttle::new(submit::COOLDOWN_PATH);
+    let remaining = throttle.remaining(SystemTime::now());
+    if !remaining.is_zero() {
+        eprintln!("wait {}s before submitting again", remaining.as_secs());
+        exit(1);
+    }
+    let (_, _, input) = read_inputs(args).pop().unwrap();
+    let result = runner::get(day)
+        .unwrap()
+        .run(day, &input, &args.parts)
+        .unwrap_or_else(|e| parse_error(&e));
+    let answer = &result.parts[0].answer;
+    if *answer == Answer::Unsolved {
+        eprintln!("day {day} part {part}: nothing to submit, unsolved");
+        exit(1);
+    }
+    let mut ledger = read_ledger();
+    if let Err(refusal) = ledger.check(day, part, answer) {
+        eprintln!("day {day} part {part}: not submitting {answer}: {refusal}");
+        exit(1);
+    }
+    println!("day {day} part {part}: submitting {answer}");
+    let response = submit::submit(&client, day, part, &answer.to_string())
+        .unwrap_or_else(|e| fetch_error(day, &e));
+    throttle
+        .record(SystemTime::now(), response.cooldown)
+        .unwrap_or_else(|e| fetch_error(day, &e));
+    println!("day {day} part {part}: {}", response.verdict);
+    if let Some(outcome) = Outcome::from_verdict(&response.verdict) {
+        ledger.record(day, part, answer, outcome);
+        let path = ledger::DEFAULT_PATH;
+        std::fs::write(path, ledger.to_string())
+            .unwrap_or_else(|e| fetch_error(day, &format!("cannot write {path}: {e}")));
+    }
+    if !matches!(response.verdict, Verdict::Correct | Verdict::AlreadySolved) {
+        exit(1);
+    }
+}
+
+fn read_ledger() -> Ledger {
+    if Path::new(ledger::DEFAULT_PATH).exists() {
+        parse_file(ledger::DEFAULT_PATH)
+    } else {
+        Ledger::default()
+    }
+}
+
+fn status(args: &Args) {
+    let ledger = read_ledger();
+    let days = days(args);
+    let mut any = false;
+    for (day, part, entry) in ledger.entries().filter(|(day, ..)| days.contains(day)) {
+        println!("day {day} part {part}: {entry}");
+        any = true;
+    }
+    if !any {
+        println!("no guesses recorded");
+    }
+}
+
+fn main() {
+    adv2023::init_logging();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args = parse_args(&args);
+    match args.command {
+        Command::Run => run(&args),
+        Command::Bench => bench(&args),
+        Command::Fetch => fetch(&args),
+        Command::Submit => submit(&args),
+        Command::Status => status(&args),
+    }
+}
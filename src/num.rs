@@ -0,0 +1,118 @@
+use ::num::{PrimInt, Signed};
+
+const OVERFLOW: &str = "overflow, try i128";
+
+// `a` modulo `m`, in 0..m for positive `m`.
+fn modulo<T: PrimInt + Signed>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::zero() {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// `(g, x, y)` such that `a * x + b * y = g`, where `g` is the greatest
+/// common divisor of `a` and `b`, never negative.
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < T::zero() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// Greatest common divisor, never negative.
+pub fn gcd<T: PrimInt + Signed>(a: T, b: T) -> T {
+    extended_gcd(a, b).0
+}
+
+/// `x` in `0..m` with `a * x = 1` modulo `m`, None if `a` and `m` share a
+/// factor.
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(modulo(a, m), m);
+    g.is_one().then(|| modulo(x, m))
+}
+
+/// Least common multiple, never negative, None if it doesn't fit.
+pub fn checked_lcm<T: PrimInt + Signed>(a: T, b: T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::zero());
+    }
+    (a / gcd(a, b)).checked_mul(&b).map(|l| l.abs())
+}
+
+/// Greatest common divisor of all `values`, 0 if there are none.
+pub fn gcd_all<T: PrimInt + Signed>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), gcd)
+}
+
+/// Least common multiple of all `values`, 1 if there are none. None if it
+/// doesn't fit, in which case i128 might.
+pub fn lcm_all<T: PrimInt + Signed>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::one(), checked_lcm)
+}
+
+/// Solves `x = r` modulo `m` for all pairs `(r, m)` at once, where the moduli
+/// don't need to be coprime. Returns the smallest `x` that isn't negative,
+/// along with the least common multiple of the moduli, after which solutions
+/// repeat. None if there is no solution.
+///
+/// Panics if the moduli aren't positive or their multiple doesn't fit.
+pub fn crt<T: PrimInt + Signed>(congruences: impl IntoIterator<Item = (T, T)>) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |(r1, m1), (r2, m2)| {
+            assert!(m2 > T::zero(), "modulus must be positive");
+            let r2 = modulo(r2, m2);
+            // With p * m1 = g modulo m2, stepping r1 by k * m1 for the k
+            // below lands on r2.
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2 - r1;
+            if !(diff % g).is_zero() {
+                return None;
+            }
+            let m2 = m2 / g;
+            let k = modulo(diff / g, m2)
+                .checked_mul(&modulo(p, m2))
+                .expect(OVERFLOW);
+            let lcm = m1.checked_mul(&m2).expect(OVERFLOW);
+            Some((r1 + m1 * modulo(k, m2), lcm))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num() {
+        assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+        assert_eq!(extended_gcd(-4, 6), (2, 1, 1));
+        assert_eq!(gcd(0, -5), 5);
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(2, 4), None);
+
+        assert_eq!(gcd_all([12, -18, 30]), 6);
+        assert_eq!(lcm_all([4, -6, 10]), Some(60));
+        assert_eq!(lcm_all(Vec::<i32>::new()), Some(1));
+        let primes = [1_000_000_007, 998_244_353, 1_000_000_009];
+        assert_eq!(lcm_all(primes), None::<i64>);
+        assert!(lcm_all(primes.map(i128::from)).is_some());
+
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt([(2, 4), (-2, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt(Vec::<(i64, i64)>::new()), Some((0, 1)));
+    }
+}
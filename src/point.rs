@@ -0,0 +1,446 @@
+use std::{
+    ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+    str::FromStr,
+};
+
+use crate::{parse_num, ParseError, Pos};
+
+/// Point or vector with `N` integer coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<const N: usize>(pub [isize; N]);
+
+/// Coordinates are (x, y, z), with z going up.
+pub type Pos3 = Point<3>;
+
+impl<const N: usize> Default for Point<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> Point<N> {
+    pub fn new(coords: [isize; N]) -> Self {
+        Self(coords)
+    }
+
+    /// Unit vector along `axis`.
+    pub fn unit(axis: usize) -> Self {
+        let mut p = Self::default();
+        p.0[axis] = 1;
+        p
+    }
+
+    fn zip_with(self, other: Self, f: impl Fn(isize, isize) -> isize) -> Self {
+        Self(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+
+    pub fn manhattan(&self, other: &Self) -> isize {
+        (0..N).map(|i| (self.0[i] - other.0[i]).abs()).sum()
+    }
+
+    /// Distance with diagonal moves allowed.
+    pub fn chebyshev(&self, other: &Self) -> isize {
+        (0..N)
+            .map(|i| (self.0[i] - other.0[i]).abs())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Unit step towards the same direction, or all zeros.
+    pub fn signum(&self) -> Self {
+        Self(self.0.map(isize::signum))
+    }
+
+    /// The 2N points one step away along a single axis.
+    pub fn neighbors(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        (0..N).flat_map(move |axis| [p - Self::unit(axis), p + Self::unit(axis)])
+    }
+
+    /// Neighbors in `0..size` along every axis.
+    pub fn neighbors_within(&self, size: &Self) -> impl Iterator<Item = Self> {
+        let max = *size - Self([1; N]);
+        self.neighbors()
+            .filter(move |n| n.in_box(&Self::default(), &max))
+    }
+
+    /// The 3^N - 1 points around, diagonals included.
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Self> {
+        let p = *self;
+        let ones = Self([1; N]);
+        Self::box_points(p - ones, p + ones).filter(move |n| *n != p)
+    }
+
+    /// Points from self to `to`, both included. Panics unless the line is
+    /// straight, along one axis or a diagonal.
+    pub fn line_to(&self, to: &Self) -> impl Iterator<Item = Self> {
+        let delta = *to - *self;
+        let len = self.chebyshev(to);
+        assert!(
+            delta.0.iter().all(|d| *d == 0 || d.abs() == len),
+            "not a straight line: {self:?} to {to:?}"
+        );
+        let (from, step) = (*self, delta.signum());
+        (0..=len).map(move |i| from + step * i)
+    }
+
+    /// Smallest and largest corner of the box containing all `points`, None if there are none.
+    pub fn bounding_box<'a>(points: impl IntoIterator<Item = &'a Self>) -> Option<(Self, Self)> {
+        points.into_iter().fold(None, |bounds, &p| match bounds {
+            None => Some((p, p)),
+            Some((min, max)) => Some((min.zip_with(p, isize::min), max.zip_with(p, isize::max))),
+        })
+    }
+
+    /// Whether self is within the box, both corners included.
+    pub fn in_box(&self, min: &Self, max: &Self) -> bool {
+        (0..N).all(|i| min.0[i] <= self.0[i] && self.0[i] <= max.0[i])
+    }
+
+    /// All points in the box, both corners included, with the first coordinate changing fastest.
+    pub fn box_points(min: Self, max: Self) -> impl Iterator<Item = Self> {
+        let mut next = (0..N).all(|i| min.0[i] <= max.0[i]).then_some(min);
+        std::iter::from_fn(move || {
+            let current = next?;
+            let mut p = current;
+            next = None;
+            for i in 0..N {
+                if p.0[i] < max.0[i] {
+                    p.0[i] += 1;
+                    next = Some(p);
+                    break;
+                }
+                p.0[i] = min.0[i];
+            }
+            Some(current)
+        })
+    }
+}
+
+impl Pos3 {
+    pub fn x(&self) -> isize {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> isize {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> isize {
+        self.0[2]
+    }
+
+    pub fn below(&self) -> Self {
+        *self - Self::unit(2)
+    }
+}
+
+/// A `Point<N>` under another name, like `Pos` with its named fields,
+/// sharing the point's distances and neighborhoods.
+pub trait AsPoint<const N: usize>: Copy + From<Point<N>> + Into<Point<N>> {
+    fn to_point(self) -> Point<N> {
+        self.into()
+    }
+
+    fn manhattan(&self, other: &Self) -> isize {
+        self.to_point().manhattan(&other.to_point())
+    }
+
+    /// Distance with diagonal moves allowed.
+    fn chebyshev(&self, other: &Self) -> isize {
+        self.to_point().chebyshev(&other.to_point())
+    }
+
+    /// The 3^N - 1 points around, diagonals included.
+    fn all_neighbors(&self) -> impl Iterator<Item = Self> + use<N, Self> {
+        self.to_point().all_neighbors().map(Self::from)
+    }
+
+    /// Neighbors in `0..size` along every axis.
+    fn neighbors_within(&self, size: &Self) -> impl Iterator<Item = Self> + use<N, Self> {
+        self.to_point()
+            .neighbors_within(&size.to_point())
+            .map(Self::from)
+    }
+
+    /// Points from self to `to`, both included, along an axis or a diagonal.
+    fn line_to(&self, to: &Self) -> impl Iterator<Item = Self> + use<N, Self> {
+        self.to_point().line_to(&to.to_point()).map(Self::from)
+    }
+}
+
+// Point<2> keeps the (y, x) order of `Pos`.
+impl From<Pos> for Point<2> {
+    fn from(pos: Pos) -> Self {
+        Self([pos.y, pos.x])
+    }
+}
+
+impl From<Point<2>> for Pos {
+    fn from(p: Point<2>) -> Self {
+        Pos {
+            y: p.0[0],
+            x: p.0[1],
+        }
+    }
+}
+
+impl AsPoint<2> for Pos {}
+
+// Arithmetic on `Pos`, done on the point it stands for, by value and by
+// reference.
+macro_rules! pos_ops {
+    ($($trait:ident $method:ident $assign:ident $assign_method:ident),*) => {$(
+        impl $trait for Pos {
+            type Output = Pos;
+
+            fn $method(self, rhs: Pos) -> Pos {
+                Point::from(self).$method(Point::from(rhs)).into()
+            }
+        }
+
+        impl $trait<&Pos> for Pos {
+            type Output = Pos;
+
+            fn $method(self, rhs: &Pos) -> Pos {
+                self.$method(*rhs)
+            }
+        }
+
+        impl $trait for &Pos {
+            type Output = Pos;
+
+            fn $method(self, rhs: &Pos) -> Pos {
+                (*self).$method(*rhs)
+            }
+        }
+
+        impl $assign for Pos {
+            fn $assign_method(&mut self, rhs: Pos) {
+                *self = (*self).$method(rhs);
+            }
+        }
+
+        impl $assign<&Pos> for Pos {
+            fn $assign_method(&mut self, rhs: &Pos) {
+                *self = (*self).$method(*rhs);
+            }
+        }
+    )*};
+}
+
+pos_ops!(Add add AddAssign add_assign, Sub sub SubAssign sub_assign);
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Self::Output {
+        (-Point::from(self)).into()
+    }
+}
+
+impl Mul<isize> for Pos {
+    type Output = Pos;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        (Point::from(self) * rhs).into()
+    }
+}
+
+impl MulAssign<isize> for Pos {
+    fn mul_assign(&mut self, rhs: isize) {
+        *self = *self * rhs;
+    }
+}
+
+impl Rem for Pos {
+    type Output = Pos;
+
+    fn rem(self, rhs: Pos) -> Self::Output {
+        (Point::from(self) % Point::from(rhs)).into()
+    }
+}
+
+impl Rem for &Pos {
+    type Output = Pos;
+
+    fn rem(self, rhs: &Pos) -> Self::Output {
+        *self % *rhs
+    }
+}
+
+impl std::iter::Sum for Pos {
+    fn sum<I: Iterator<Item = Pos>>(iter: I) -> Self {
+        iter.map(Point::from).sum::<Point<2>>().into()
+    }
+}
+
+impl<'a> std::iter::Sum<&'a Pos> for Pos {
+    fn sum<I: Iterator<Item = &'a Pos>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl<const N: usize> Index<usize> for Point<N> {
+    type Output = isize;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for Point<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<const N: usize> Add for Point<N> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a + b)
+    }
+}
+
+impl<const N: usize> AddAssign for Point<N> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<const N: usize> Sub for Point<N> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a - b)
+    }
+}
+
+impl<const N: usize> SubAssign for Point<N> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<const N: usize> Neg for Point<N> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|a| -a))
+    }
+}
+
+impl<const N: usize> Mul<isize> for Point<N> {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self(self.0.map(|a| a * rhs))
+    }
+}
+
+impl<const N: usize> MulAssign<isize> for Point<N> {
+    fn mul_assign(&mut self, rhs: isize) {
+        *self = *self * rhs;
+    }
+}
+
+// Euclidean remainder of each coordinate, so the result is never negative.
+impl<const N: usize> Rem for Point<N> {
+    type Output = Self;
+
+    fn rem(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, isize::rem_euclid)
+    }
+}
+
+impl<const N: usize> std::iter::Sum for Point<N> {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Self::add)
+    }
+}
+
+impl<const N: usize> FromIterator<isize> for Point<N> {
+    /// Panics unless there are exactly N items.
+    fn from_iter<T: IntoIterator<Item = isize>>(iter: T) -> Self {
+        let coords: Vec<isize> = iter.into_iter().collect();
+        Self(
+            coords
+                .try_into()
+                .unwrap_or_else(|_| panic!("expected {N} items")),
+        )
+    }
+}
+
+impl<const N: usize> FromStr for Point<N> {
+    type Err = ParseError;
+
+    /// Parses comma separated coordinates, like "1, 2, 3".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coords: Vec<isize> = s
+            .split(',')
+            .map(|c| parse_num(c.trim()))
+            .collect::<Result<_, _>>()?;
+        let coords = coords
+            .try_into()
+            .map_err(|_| ParseError::new(s, format!("{N} coordinates")))?;
+        Ok(Self(coords))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point() {
+        let a = Pos3::new([1, 2, 3]);
+        let b: Pos3 = "-1, 0,4".parse().unwrap();
+        assert_eq!(a + b, Pos3::new([0, 2, 7]));
+        assert_eq!(a - b, Pos3::new([2, 2, -1]));
+        assert_eq!(-a * 2, Pos3::new([-2, -4, -6]));
+        assert_eq!([a, b].into_iter().sum::<Pos3>(), a + b);
+        assert_eq!((a.x(), a.y(), a.z()), (1, 2, 3));
+        assert_eq!(a.below(), Pos3::new([1, 2, 2]));
+        assert_eq!(a.manhattan(&b), 5);
+        assert_eq!(a.chebyshev(&b), 2);
+        assert_eq!(a.neighbors().count(), 6);
+        assert_eq!(a.all_neighbors().count(), 26);
+        assert!(a.all_neighbors().all(|n| n.chebyshev(&a) == 1));
+
+        assert_eq!(
+            Pos3::bounding_box(&[a, b]),
+            Some((Pos3::new([-1, 0, 3]), Pos3::new([1, 2, 4])))
+        );
+        assert_eq!(Pos3::bounding_box(&[]), None);
+        assert!(!a.in_box(&b, &Pos3::new([1, 2, 4])));
+        assert!(a.in_box(&Pos3::default(), &a));
+
+        let square: Vec<_> = Point::box_points(Point([0, 0]), Point([1, 1])).collect();
+        assert_eq!(
+            square,
+            vec![Point([0, 0]), Point([1, 0]), Point([0, 1]), Point([1, 1])]
+        );
+        assert_eq!(Point::box_points(Point([1]), Point([0])).count(), 0);
+
+        assert_eq!(Pos::from(Point([2, 3])), Pos::new(2, 3));
+        assert_eq!(Point::<2>::from(Pos::new(2, 3))[1], 3);
+        assert!("1,2".parse::<Pos3>().is_err());
+        assert_eq!([4, 5].into_iter().collect::<Point<2>>(), Point([4, 5]));
+
+        assert_eq!(Point([2, -3]) % Point([2, 2]), Point([0, 1]));
+        assert_eq!(Point([0, 0]).neighbors_within(&Point([2, 2])).count(), 2);
+        assert_eq!(
+            Point([0, 0]).line_to(&Point([2, -2])).collect::<Vec<_>>(),
+            vec![Point([0, 0]), Point([1, -1]), Point([2, -2])]
+        );
+
+        // Pos gets the same operations through Point<2>.
+        let (p, q) = (Pos::new(2, -3), Pos::new(-1, 1));
+        assert_eq!(p - q, Pos::new(3, -4));
+        assert_eq!([p, q, q].iter().sum::<Pos>(), Pos::new(0, -1));
+        assert_eq!((p.manhattan(&q), p.chebyshev(&q)), (7, 4));
+        assert_eq!(p.all_neighbors().count(), 8);
+        assert_eq!(p.line_to(&p).collect::<Vec<_>>(), vec![p]);
+    }
+}
@@ -1,76 +1,221 @@
-use std::cmp::{max, min};
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+    ops::RangeInclusive,
+};
 
-#[derive(Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
-pub struct Range {
-    pub from: isize,
+use num::PrimInt;
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub struct Range<T> {
+    pub from: T,
     // non-inclusive
-    pub to: isize,
+    pub to: T,
 }
 
-impl Range {
-    pub fn new(from: isize, to: isize) -> Self {
+impl<T: PrimInt> Range<T> {
+    pub fn new(from: T, to: T) -> Self {
         Self { from, to }
     }
+
+    /// Range from `from` to `last`, both included.
+    pub fn inclusive(from: T, last: T) -> Self {
+        Self::new(from, last + T::one())
+    }
+
+    pub fn to_inclusive(&self) -> RangeInclusive<T> {
+        self.from..=self.to - T::one()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from >= self.to
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.from <= value && value < self.to
+    }
+}
+
+impl<T: PrimInt> From<std::ops::Range<T>> for Range<T> {
+    fn from(r: std::ops::Range<T>) -> Self {
+        Self::new(r.start, r.end)
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for Range<T> {
+    fn from(r: RangeInclusive<T>) -> Self {
+        Self::inclusive(*r.start(), *r.end())
+    }
 }
 
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
-pub struct Ranges(Vec<Range>);
+/// Set of integers, kept as ranges that neither overlap nor touch, keyed by
+/// their start so that inserts, removals and lookups take O(log n) plus the
+/// number of ranges touched.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ranges<T>(BTreeMap<T, T>);
 
-impl Ranges {
+impl<T> Default for Ranges<T> {
+    fn default() -> Self {
+        Self(BTreeMap::new())
+    }
+}
+
+impl<T: PrimInt> Ranges<T> {
     pub fn empty() -> Self {
-        Self(vec![])
+        Self::default()
+    }
+
+    pub fn new(from: T, to: T) -> Self {
+        Range::new(from, to).into()
     }
 
-    pub fn new(from: isize, to: isize) -> Self {
-        Self(vec![Range { from, to }])
+    pub fn from_slice(arr: &[(T, T)]) -> Self {
+        arr.iter().map(|&(from, to)| Range::new(from, to)).collect()
     }
 
-    pub fn from_slice(arr: &[(isize, isize)]) -> Self {
-        Self(arr.iter().map(|&(from, to)| Range::new(from, to)).collect())
+    // The range containing `value` or ending right at it, if any.
+    fn range_at(&self, value: T) -> Option<Range<T>> {
+        let (&from, &to) = self.0.range(..=value).next_back()?;
+        (to >= value).then_some(Range { from, to })
     }
 
-    fn merge(&mut self) {
-        let mut idx = 0;
-        while (idx + 1) < self.0.len() {
-            if self.0[idx].to >= self.0[idx + 1].from {
-                self.0[idx].to = max(self.0[idx].to, self.0[idx + 1].to);
-                self.0.remove(idx + 1);
-            } else {
-                idx += 1;
+    /// Adds all of `r`, merging with the ranges it overlaps or touches.
+    pub fn insert(&mut self, r: Range<T>) {
+        if r.is_empty() {
+            return;
+        }
+        let Range { mut from, mut to } = r;
+        if let Some(before) = self.range_at(from) {
+            self.0.remove(&before.from);
+            from = before.from;
+            to = max(to, before.to);
+        }
+        while let Some((&start, &end)) = self.0.range(from..=to).next() {
+            self.0.remove(&start);
+            to = max(to, end);
+        }
+        self.0.insert(from, to);
+    }
+
+    /// Takes out all of `r`, splitting the ranges it cuts through.
+    pub fn remove(&mut self, r: Range<T>) {
+        if r.is_empty() {
+            return;
+        }
+        if let Some(before) = self.range_at(r.from).filter(|b| b.from < r.from) {
+            self.0.insert(before.from, r.from);
+            if before.to > r.to {
+                self.0.insert(r.to, before.to);
+            }
+        }
+        while let Some((&start, &end)) = self.0.range(r.from..r.to).next() {
+            self.0.remove(&start);
+            if end > r.to {
+                self.0.insert(r.to, end);
             }
         }
     }
 
-    pub fn union(&self, other: &Ranges) -> Ranges {
-        let mut new = self.clone();
-        new.0.extend(other.0.clone());
-        new.0.sort();
-        new.merge();
+    pub fn union(&self, other: &Ranges<T>) -> Ranges<T> {
+        let (mut new, smaller) = if self.0.len() >= other.0.len() {
+            (self.clone(), other)
+        } else {
+            (other.clone(), self)
+        };
+        smaller.ranges().for_each(|r| new.insert(r));
         new
     }
 
-    pub fn intersect_one(&self, one: &Range) -> Ranges {
-        // dbg!(self, one);
-        let mut out: Vec<Range> = vec![];
-        for r in self.0.iter() {
-            if r.from >= one.to {
-                break;
-            }
-            if r.to > one.from && one.to > r.from {
-                let from = max(r.from, one.from);
-                let to = min(r.to, one.to);
-                out.push(Range { from, to });
-            }
+    pub fn intersect_one(&self, one: &Range<T>) -> Ranges<T> {
+        if one.is_empty() {
+            return Ranges::empty();
         }
+        let before = self
+            .range_at(one.from)
+            .filter(|b| b.from < one.from && b.to > one.from);
+        let inside = self
+            .0
+            .range(one.from..one.to)
+            .map(|(&from, &to)| Range { from, to });
+        let out = before
+            .into_iter()
+            .chain(inside)
+            .map(|r| (max(r.from, one.from), min(r.to, one.to)))
+            .collect();
         Ranges(out)
     }
 
+    pub fn intersection(&self, other: &Ranges<T>) -> Ranges<T> {
+        other
+            .ranges()
+            .flat_map(|r| self.intersect_one(&r).0)
+            .map(|(from, to)| Range { from, to })
+            .collect()
+    }
+
+    /// Members of self that aren't in `other`.
+    pub fn difference(&self, other: &Ranges<T>) -> Ranges<T> {
+        let mut new = self.clone();
+        other.ranges().for_each(|r| new.remove(r));
+        new
+    }
+
+    /// Everything within `bounds` that isn't in self.
+    pub fn complement(&self, bounds: &Range<T>) -> Ranges<T> {
+        Ranges::from(*bounds).difference(self)
+    }
+
+    /// Members of exactly one of self and `other`.
+    pub fn symmetric_difference(&self, other: &Ranges<T>) -> Ranges<T> {
+        self.difference(other).union(&other.difference(self))
+    }
+
+    /// Number of members.
     pub fn len(&self) -> usize {
-        let mut sum = 0usize;
-        for &Range { from, to } in self.0.iter() {
-            sum += (to - from) as usize;
-        }
-        sum
+        self.ranges()
+            .map(|r| (r.to - r.from).to_usize().unwrap())
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.range_at(value).is_some_and(|r| r.contains(value))
+    }
+
+    /// The separate ranges, in order.
+    pub fn ranges(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.0.iter().map(|(&from, &to)| Range { from, to })
+    }
+
+    /// All members, in order.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.ranges().flat_map(|r| num::range(r.from, r.to))
+    }
+
+    pub fn shift(&self, offset: T) -> Ranges<T> {
+        Ranges(
+            self.0
+                .iter()
+                .map(|(&from, &to)| (from + offset, to + offset))
+                .collect(),
+        )
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for Ranges<T> {
+    fn from(r: Range<T>) -> Self {
+        std::iter::once(r).collect()
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for Ranges<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut new = Ranges::empty();
+        iter.into_iter().for_each(|r| new.insert(r));
+        new
     }
 }
 
@@ -145,4 +290,66 @@ mod tests {
             Ranges::new(3, 4)
         );
     }
+
+    #[test]
+    fn test_set_algebra() {
+        let a = Ranges::from_slice(&[(0u64, 10), (20, 30)]);
+        let b = Ranges::from_slice(&[(5u64, 25), (28, 29)]);
+        assert_eq!(
+            a.intersection(&b),
+            Ranges::from_slice(&[(5, 10), (20, 25), (28, 29)])
+        );
+        assert_eq!(
+            a.difference(&b),
+            Ranges::from_slice(&[(0, 5), (25, 28), (29, 30)])
+        );
+        assert_eq!(b.difference(&a), Ranges::new(10, 20));
+        assert_eq!(
+            a.symmetric_difference(&b),
+            Ranges::from_slice(&[(0, 5), (10, 20), (25, 28), (29, 30)])
+        );
+        assert_eq!(
+            a.complement(&Range::new(0, 40)),
+            Ranges::from_slice(&[(10, 20), (30, 40)])
+        );
+        assert!(a.difference(&a).is_empty());
+        assert!(Ranges::new(3, 3).is_empty());
+
+        assert!(a.contains(0) && a.contains(29));
+        assert!(!a.contains(10) && !a.contains(30));
+        assert_eq!(a.shift(5), Ranges::from_slice(&[(5, 15), (25, 35)]));
+        assert_eq!(
+            b.iter().collect::<Vec<_>>(),
+            (5..25).chain([28]).collect::<Vec<_>>()
+        );
+
+        let r = Range::inclusive(-2i8, 3);
+        assert_eq!(r, Range::from(-2..4));
+        assert_eq!(r, Range::from(-2..=3));
+        assert_eq!(r.to_inclusive(), -2..=3);
+        assert_eq!(Ranges::from(r).len(), 6);
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut r = Ranges::empty();
+        r.insert(Range::new(10, 20));
+        r.insert(Range::new(30, 40));
+        r.insert(Range::new(0, 5));
+        assert_eq!(r, Ranges::from_slice(&[(0, 5), (10, 20), (30, 40)]));
+        r.insert(Range::new(20, 30));
+        assert_eq!(r, Ranges::from_slice(&[(0, 5), (10, 40)]));
+        r.insert(Range::new(3, 12));
+        assert_eq!(r, Ranges::new(0, 40));
+
+        r.remove(Range::new(5, 10));
+        r.remove(Range::new(35, 50));
+        assert_eq!(r, Ranges::from_slice(&[(0, 5), (10, 35)]));
+        r.remove(Range::new(-5, 12));
+        assert_eq!(r, Ranges::new(12, 35));
+        r.remove(Range::new(20, 20));
+        assert_eq!(r.len(), 23);
+        r.remove(Range::new(12, 35));
+        assert!(r.is_empty());
+    }
 }
@@ -0,0 +1,255 @@
+use std::{
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crate::*;
+
+/// Result of solving one part.
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub part: u8,
+    pub answer: Answer,
+    pub time: Duration,
+}
+
+/// Result of running one day: how long parsing took, plus each requested part.
+#[derive(Debug, Clone)]
+pub struct DayResult {
+    pub day: u8,
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+/// Object-safe wrapper around `Solution` so that days can be picked at runtime.
+pub trait Runnable: Sync {
+    fn run(&self, day: u8, input: &str, parts: &[u8]) -> Result<DayResult, ParseError>;
+}
+
+impl<S: Solution + Sync> Runnable for S {
+    fn run(&self, day: u8, input: &str, parts: &[u8]) -> Result<DayResult, ParseError> {
+        let start = Instant::now();
+        let parsed = S::parse(input).map_err(|e| e.locate(day, input))?;
+        let parse_time = start.elapsed();
+
+        let parts = parts
+            .iter()
+            .map(|&part| {
+                let start = Instant::now();
+                let answer = match part {
+                    1 => S::part1(&parsed),
+                    2 => S::part2(&parsed),
+                    _ => panic!("no such part: {part}"),
+                };
+                PartResult {
+                    part,
+                    answer,
+                    time: start.elapsed(),
+                }
+            })
+            .collect();
+        Ok(DayResult {
+            day,
+            parse_time,
+            parts,
+        })
+    }
+}
+
+pub const DAYS: [&dyn Runnable; 25] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+    &day25::Day25,
+];
+
+/// Returns the solution for given day (1-based), if there is one.
+pub fn get(day: u8) -> Option<&'static dyn Runnable> {
+    DAYS.get((day as usize).checked_sub(1)?).copied()
+}
+
+/// Default input location for a day.
+pub fn input_path(day: u8) -> String {
+    format!("input/day{day}.txt")
+}
+
+/// Known answers for the examples, checked by the tests and `--example --check`.
+pub const EXAMPLE_ANSWERS_PATH: &str = "examples/answers.toml";
+
+/// Example input for a part: `examples/dayN-partN.txt` where the part has its own example,
+/// otherwise `examples/dayN.txt`.
+pub fn example_path(day: u8, part: u8) -> String {
+    let path = format!("examples/day{day}-part{part}.txt");
+    if std::path::Path::new(&path).exists() {
+        path
+    } else {
+        format!("examples/day{day}.txt")
+    }
+}
+
+/// How results are printed on stdout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    // "day 1 part 2: 1234"
+    Plain,
+    // One JSON object per line.
+    Json,
+    // Tab separated, with a header line.
+    Tsv,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(OutputFormat::Plain),
+            "json" => Ok(OutputFormat::Json),
+            "tsv" => Ok(OutputFormat::Tsv),
+            _ => Err(format!("unknown format: {s}")),
+        }
+    }
+}
+
+pub const TSV_HEADER: &str = "day\tpart\tanswer\tparse_us\tsolve_us";
+
+fn json_string(s: &str) -> String {
+    let mut out = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Int(v) => v.to_string(),
+        Answer::U128(v) => v.to_string(),
+        Answer::Str(s) => json_string(s),
+        Answer::Unsolved => "null".to_string(),
+    }
+}
+
+impl DayResult {
+    /// Formats each part as one line of output.
+    pub fn format(&self, format: OutputFormat) -> Vec<String> {
+        let parse_us = self.parse_time.as_micros();
+        self.parts
+            .iter()
+            .map(|p| {
+                let (day, part, solve_us) = (self.day, p.part, p.time.as_micros());
+                match format {
+                    OutputFormat::Plain => format!("day {day} part {part}: {}", p.answer),
+                    OutputFormat::Json => format!(
+                        "{{\"day\":{day},\"part\":{part},\"answer\":{},\"parse_us\":{parse_us},\"solve_us\":{solve_us}}}",
+                        json_answer(&p.answer)
+                    ),
+                    OutputFormat::Tsv => {
+                        let answer = match &p.answer {
+                            Answer::Unsolved => String::new(),
+                            a => a.to_string().replace(['\t', '\n'], " "),
+                        };
+                        format!("{day}\t{part}\t{answer}\t{parse_us}\t{solve_us}")
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answers::{Answers, CheckStatus};
+
+    #[test]
+    fn test_format() {
+        let result = DayResult {
+            day: 3,
+            parse_time: Duration::from_micros(5),
+            parts: vec![
+                PartResult {
+                    part: 1,
+                    answer: 42usize.into(),
+                    time: Duration::from_micros(7),
+                },
+                PartResult {
+                    part: 2,
+                    answer: "a\"b".into(),
+                    time: Duration::from_micros(9),
+                },
+            ],
+        };
+        assert_eq!(
+            result.format(OutputFormat::Plain),
+            vec!["day 3 part 1: 42", "day 3 part 2: a\"b"]
+        );
+        assert_eq!(
+            result.format(OutputFormat::Json),
+            vec![
+                r#"{"day":3,"part":1,"answer":42,"parse_us":5,"solve_us":7}"#,
+                r#"{"day":3,"part":2,"answer":"a\"b","parse_us":5,"solve_us":9}"#,
+            ]
+        );
+        assert_eq!(
+            result.format(OutputFormat::Tsv),
+            vec!["3\t1\t42\t5\t7", "3\t2\ta\"b\t5\t9"]
+        );
+        assert_eq!(Answer::from(u64::MAX), Answer::U128(u64::MAX as u128));
+    }
+
+    #[test]
+    fn test_examples() {
+        let answers: Answers = std::fs::read_to_string(EXAMPLE_ANSWERS_PATH)
+            .unwrap()
+            .parse()
+            .unwrap();
+        let mut failed = vec![];
+        for day in 1..=DAYS.len() as u8 {
+            for part in [1, 2] {
+                if answers.get(day, part).is_none() {
+                    continue;
+                }
+                let input = std::fs::read_to_string(example_path(day, part)).unwrap();
+                let result = get(day).unwrap().run(day, &input, &[part]).unwrap();
+                failed.extend(
+                    answers
+                        .check(&result)
+                        .iter()
+                        .filter(|c| c.status != CheckStatus::Pass)
+                        .map(|c| c.to_string()),
+                );
+            }
+        }
+        assert!(failed.is_empty(), "{failed:#?}");
+    }
+}
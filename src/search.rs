@@ -0,0 +1,437 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+};
+
+/// How much work a search did.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    // States taken off the frontier and their neighbors looked at.
+    pub expanded: usize,
+    // Pushes onto the frontier, counting improvements of already pushed states.
+    pub pushed: usize,
+}
+
+/// Cheapest path found, from a start to a goal, both included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<S> {
+    pub cost: usize,
+    pub states: Vec<S>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S> {
+    // None if no goal can be reached.
+    pub path: Option<Path<S>>,
+    pub stats: Stats,
+}
+
+/// Finds the cheapest path from any of `starts` to any state for which
+/// `is_goal` is true. `neighbors` gives the states one step away with the
+/// cost of the step.
+pub fn dijkstra<S, N>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> N,
+    is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = (S, usize)>,
+{
+    astar(starts, neighbors, |_| 0, is_goal)
+}
+
+// Everything seen so far, with states numbered in the order they are first seen.
+struct Visited<S> {
+    states: Vec<S>,
+    index: HashMap<S, usize>,
+    // Best known cost and the state it was reached from.
+    best: Vec<(usize, Option<usize>)>,
+    done: Vec<bool>,
+    // Ordered by estimated total cost, then by cost so far.
+    frontier: BinaryHeap<Reverse<(usize, usize, usize)>>,
+    stats: Stats,
+}
+
+impl<S: Hash + Eq + Clone> Visited<S> {
+    fn push(
+        &mut self,
+        state: S,
+        cost: usize,
+        prev: Option<usize>,
+        heuristic: impl FnOnce(&S) -> usize,
+    ) {
+        let i = match self.index.get(&state) {
+            Some(&i) if self.done[i] || self.best[i].0 <= cost => return,
+            Some(&i) => {
+                self.best[i] = (cost, prev);
+                i
+            }
+            None => {
+                let i = self.states.len();
+                self.index.insert(state.clone(), i);
+                self.states.push(state);
+                self.best.push((cost, prev));
+                self.done.push(false);
+                i
+            }
+        };
+        let estimate = cost + heuristic(&self.states[i]);
+        self.frontier.push(Reverse((estimate, cost, i)));
+        self.stats.pushed += 1;
+    }
+
+    // Next state to expand, skipping entries that were improved on since pushed.
+    fn pop(&mut self) -> Option<(usize, usize)> {
+        while let Some(Reverse((_, cost, i))) = self.frontier.pop() {
+            if !self.done[i] && self.best[i].0 == cost {
+                self.done[i] = true;
+                self.stats.expanded += 1;
+                return Some((i, cost));
+            }
+        }
+        None
+    }
+
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].clone()];
+        while let Some(prev) = self.best[i].1 {
+            path.push(self.states[prev].clone());
+            i = prev;
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Like `dijkstra`, but guided by `heuristic`, which must be consistent: never
+/// more than the cost of a step plus the heuristic after it, and 0 at goals.
+/// Finished states are never reopened, so an admissible but inconsistent
+/// heuristic can miss the cheapest path.
+pub fn astar<S, N>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> SearchResult<S>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = (S, usize)>,
+{
+    let mut visited = Visited {
+        states: vec![],
+        index: HashMap::new(),
+        best: vec![],
+        done: vec![],
+        frontier: BinaryHeap::new(),
+        stats: Stats::default(),
+    };
+    for start in starts {
+        visited.push(start, 0, None, &mut heuristic);
+    }
+    while let Some((i, cost)) = visited.pop() {
+        if is_goal(&visited.states[i]) {
+            return SearchResult {
+                path: Some(Path {
+                    cost,
+                    states: visited.path_to(i),
+                }),
+                stats: visited.stats,
+            };
+        }
+        for (next, step) in neighbors(&visited.states[i]) {
+            visited.push(next, cost + step, Some(i), &mut heuristic);
+        }
+    }
+    SearchResult {
+        path: None,
+        stats: visited.stats,
+    }
+}
+
+/// Number of steps from the nearest of `sources` to every reachable state.
+pub fn bfs<S, N>(
+    sources: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+) -> HashMap<S, usize>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+{
+    let mut distance = HashMap::new();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if !distance.contains_key(&source) {
+            distance.insert(source.clone(), 0);
+            queue.push_back((source, 0));
+        }
+    }
+    while let Some((state, dist)) = queue.pop_front() {
+        for next in neighbors(&state) {
+            if !distance.contains_key(&next) {
+                distance.insert(next.clone(), dist + 1);
+                queue.push_back((next, dist + 1));
+            }
+        }
+    }
+    distance
+}
+
+/// Path with the fewest steps from any of `sources` to a goal, both included.
+pub fn bfs_path<S, N>(
+    sources: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Vec<S>>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+{
+    let mut prev: HashMap<S, Option<S>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if !prev.contains_key(&source) {
+            prev.insert(source.clone(), None);
+            queue.push_back(source);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        if is_goal(&state) {
+            let mut path = vec![state];
+            while let Some(Some(p)) = prev.get(path.last().unwrap()) {
+                path.push(p.clone());
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for next in neighbors(&state) {
+            if !prev.contains_key(&next) {
+                prev.insert(next.clone(), Some(state.clone()));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// All states reachable from `sources` through passable states. Sources
+/// are included only if passable.
+pub fn flood_fill<S, N>(
+    sources: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+    mut passable: impl FnMut(&S) -> bool,
+) -> HashSet<S>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+{
+    let mut filled = HashSet::new();
+    let mut stack = vec![];
+    for source in sources {
+        if passable(&source) && filled.insert(source.clone()) {
+            stack.push(source);
+        }
+    }
+    while let Some(state) = stack.pop() {
+        for next in neighbors(&state) {
+            if !filled.contains(&next) && passable(&next) {
+                filled.insert(next.clone());
+                stack.push(next);
+            }
+        }
+    }
+    filled
+}
+
+/// Splits `states` into groups reachable from each other. Assumes that
+/// `neighbors` goes both ways.
+pub fn components<S, N>(
+    states: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> N,
+) -> Vec<HashSet<S>>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+{
+    let mut seen = HashSet::new();
+    let mut out = vec![];
+    for state in states {
+        if seen.contains(&state) {
+            continue;
+        }
+        let component = flood_fill([state], &mut neighbors, |_| true);
+        seen.extend(component.iter().cloned());
+        out.push(component);
+    }
+    out
+}
+
+/// States first reached at one step of a breadth-first expansion.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layer<S> {
+    pub step: usize,
+    pub states: Vec<S>,
+    // States reached at this step or an earlier one of the same parity. When
+    // every step can be undone, as on a grid, these are where a walk of
+    // exactly `step` steps can end.
+    pub same_parity: usize,
+}
+
+/// Iterator over the layers of a breadth-first expansion, see `layers`.
+pub struct Layers<S, F> {
+    frontier: Vec<S>,
+    seen: HashSet<S>,
+    neighbors: F,
+    step: usize,
+    // Number of states seen at even and odd steps.
+    parity_counts: [usize; 2],
+}
+
+/// Expands from `sources` one step at a time, starting with the sources as step 0.
+pub fn layers<S, N, F>(sources: impl IntoIterator<Item = S>, neighbors: F) -> Layers<S, F>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+    F: FnMut(&S) -> N,
+{
+    let mut seen = HashSet::new();
+    let frontier = sources
+        .into_iter()
+        .filter(|s| seen.insert(s.clone()))
+        .collect();
+    Layers {
+        frontier,
+        seen,
+        neighbors,
+        step: 0,
+        parity_counts: [0, 0],
+    }
+}
+
+impl<S, N, F> Iterator for Layers<S, F>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+    F: FnMut(&S) -> N,
+{
+    type Item = Layer<S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.frontier.is_empty() {
+            return None;
+        }
+        let mut next = vec![];
+        for state in &self.frontier {
+            for n in (self.neighbors)(state) {
+                if self.seen.insert(n.clone()) {
+                    next.push(n);
+                }
+            }
+        }
+        let states = std::mem::replace(&mut self.frontier, next);
+        self.parity_counts[self.step % 2] += states.len();
+        let layer = Layer {
+            step: self.step,
+            states,
+            same_parity: self.parity_counts[self.step % 2],
+        };
+        self.step += 1;
+        Some(layer)
+    }
+}
+
+/// States where a walk of exactly `steps` steps from one of `sources` can
+/// end, assuming every step can be undone, as on a grid.
+pub fn reachable_in<S, N>(
+    sources: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> N,
+    steps: usize,
+) -> HashSet<S>
+where
+    S: Hash + Eq + Clone,
+    N: IntoIterator<Item = S>,
+{
+    layers(sources, neighbors)
+        .take_while(|layer| layer.step <= steps)
+        .filter(|layer| layer.step % 2 == steps % 2)
+        .flat_map(|layer| layer.states)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AsPoint, Grid, Pos};
+
+    #[test]
+    fn test_search() {
+        let grid: Grid<char> = "S..#....\n.#.#.##.\n.#...#E.\n.####...\n".parse().unwrap();
+        let start = grid.find(&'S').unwrap();
+        let end = grid.find(&'E').unwrap();
+        let neighbors = |p: &Pos| {
+            grid.neighbors(*p)
+                .filter(|n| grid[*n] != '#')
+                .map(|n| (n, 1))
+                .collect::<Vec<_>>()
+        };
+
+        let found = dijkstra([start], neighbors, |p| *p == end);
+        let path = found.path.unwrap();
+        assert_eq!(path.cost, 14);
+        assert_eq!(path.states.len(), 15);
+        assert_eq!((path.states[0], path.states[14]), (start, end));
+        assert!(path.states.windows(2).all(|w| w[0].manhattan(&w[1]) == 1));
+
+        let guided = astar(
+            [start],
+            neighbors,
+            |p| p.manhattan(&end) as usize,
+            |p| *p == end,
+        );
+        assert_eq!(guided.path.unwrap().cost, 14);
+        assert!(guided.stats.expanded <= found.stats.expanded);
+        assert!(found.stats.pushed >= found.stats.expanded);
+
+        // Either corner on the right will do; the top one is closer.
+        let goals = [Pos::new(0, 7), Pos::new(3, 7)];
+        let found = dijkstra([start], neighbors, |p| goals.contains(p));
+        assert_eq!(found.path.unwrap().states.last(), Some(&Pos::new(0, 7)));
+
+        let found = dijkstra([start], neighbors, |p| *p == Pos::new(0, 3));
+        assert_eq!(found.path, None);
+        assert_eq!(found.stats.expanded, 21);
+    }
+
+    #[test]
+    fn test_bfs() {
+        // Two islands of '.', split by the wall.
+        let grid: Grid<char> = "..#.\n..#.\n###.\n".parse().unwrap();
+        let open = |c: &char| *c == '.';
+        let dist = grid.distances([Pos::new(0, 0)], open);
+        assert_eq!(dist.len(), 4);
+        assert_eq!(dist[&Pos::new(1, 1)], 2);
+        assert_eq!(grid.flood_fill([Pos::new(0, 3)], open).len(), 3);
+        assert!(grid.flood_fill([Pos::new(0, 2)], open).is_empty());
+        let mut sizes: Vec<_> = grid.components(open).iter().map(|c| c.len()).collect();
+        sizes.sort();
+        assert_eq!(sizes, vec![3, 4]);
+
+        // A line of numbers, each connected to the ones next to it.
+        let line = |n: &i32| [n - 1, n + 1].into_iter().filter(|m| (0..10).contains(m));
+        assert_eq!(bfs([0, 9], line)[&4], 4);
+        assert_eq!(bfs_path([2], line, |n| *n == 5), Some(vec![2, 3, 4, 5]));
+        assert_eq!(bfs_path([2], line, |n| *n == 10), None);
+        assert_eq!(flood_fill([5], line, |n| *n != 3).len(), 6);
+
+        let all: Vec<_> = layers([3], line).collect();
+        assert_eq!(all.len(), 7);
+        assert_eq!(all[1].states.len(), 2);
+        assert_eq!(
+            all.iter().map(|l| l.same_parity).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 4, 5, 5]
+        );
+        let ends = reachable_in([3], line, 2);
+        assert_eq!(ends, HashSet::from([1, 3, 5]));
+    }
+}
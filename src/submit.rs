@@ -0,0 +1,218 @@
+use std::{
+    fmt, fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::fetch::Client;
+
+/// Where the time of the next allowed submission is kept between runs.
+pub const COOLDOWN_PATH: &str = ".aoc-cooldown";
+
+/// Minimum time between any two submissions, whatever the site says.
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+// The site asks for a minute after a wrong answer, unless it says otherwise.
+const WRONG_ANSWER_WAIT: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    // Wrong, without a hint which way.
+    Incorrect,
+    // Submitted too soon after the previous answer, nothing was checked.
+    Wait(Duration),
+    AlreadySolved,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Incorrect => write!(f, "wrong"),
+            Verdict::Wait(d) => write!(f, "too soon, wait {}s", d.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+/// Parsed answer page: the verdict and how long to wait before the next submission.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub verdict: Verdict,
+    pub cooldown: Duration,
+}
+
+lazy_static! {
+    static ref ARTICLE: Regex = Regex::new(r"(?s)<article[^>]*>(.*?)</article>").unwrap();
+    static ref TAG: Regex = Regex::new(r"<[^>]*>").unwrap();
+    static ref LEFT_TO_WAIT: Regex =
+        Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+    static ref PLEASE_WAIT: Regex = Regex::new(r"(?i)please wait (one|\d+) minutes?").unwrap();
+}
+
+/// Parses the page returned for a submitted answer.
+pub fn parse_response(html: &str) -> Result<Response, String> {
+    let article = ARTICLE
+        .captures(html)
+        .ok_or_else(|| format!("unexpected response: {}", html.trim()))?;
+    let text = TAG.replace_all(&article[1], "");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    if let Some(c) = LEFT_TO_WAIT.captures(&text) {
+        let minutes: u64 = c.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+        let seconds: u64 = c[2].parse().unwrap();
+        let wait = Duration::from_secs(minutes * 60 + seconds);
+        return Ok(Response {
+            verdict: Verdict::Wait(wait),
+            cooldown: wait.max(MIN_INTERVAL),
+        });
+    }
+    let verdict = if text.contains("That's the right answer") {
+        Verdict::Correct
+    } else if text.contains("Did you already complete it") {
+        Verdict::AlreadySolved
+    } else if text.contains("your answer is too high") {
+        Verdict::TooHigh
+    } else if text.contains("your answer is too low") {
+        Verdict::TooLow
+    } else if text.contains("That's not the right answer") {
+        Verdict::Incorrect
+    } else {
+        return Err(format!("unexpected response: {text}"));
+    };
+    let cooldown = match verdict {
+        Verdict::TooHigh | Verdict::TooLow | Verdict::Incorrect => {
+            match PLEASE_WAIT.captures(&text) {
+                Some(c) if &c[1] == "one" => Duration::from_secs(60),
+                Some(c) => Duration::from_secs(c[1].parse::<u64>().unwrap() * 60),
+                None => WRONG_ANSWER_WAIT,
+            }
+        }
+        _ => MIN_INTERVAL,
+    };
+    Ok(Response { verdict, cooldown })
+}
+
+/// Posts the answer and parses the response.
+pub fn submit(client: &Client, day: u8, part: u8, answer: &str) -> Result<Response, String> {
+    parse_response(&client.post_answer(day, part, answer)?)
+}
+
+/// Client-side cooldown between submissions, kept in a file so it lasts across runs.
+pub struct Throttle {
+    path: PathBuf,
+}
+
+impl Throttle {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// How long until the next submission is allowed.
+    pub fn remaining(&self, now: SystemTime) -> Duration {
+        let Ok(s) = fs::read_to_string(&self.path) else {
+            return Duration::ZERO;
+        };
+        let Ok(secs) = s.trim().parse() else {
+            return Duration::ZERO;
+        };
+        let next = UNIX_EPOCH + Duration::from_secs(secs);
+        next.duration_since(now).unwrap_or_default()
+    }
+
+    /// Blocks submissions for `cooldown` from `now`.
+    pub fn record(&self, now: SystemTime, cooldown: Duration) -> Result<(), String> {
+        let next = (now + cooldown.max(MIN_INTERVAL))
+            .duration_since(UNIX_EPOCH)
+            .unwrap();
+        // Rounded up, so the wait is never cut short.
+        let secs = next.as_secs() + u64::from(next.subsec_nanos() > 0);
+        fs::write(&self.path, format!("{secs}\n"))
+            .map_err(|e| format!("cannot write {}: {e}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::mock::MockServer;
+
+    fn page(text: &str) -> String {
+        format!("<html><body><main>\n<article><p>{text}</p></article>\n</main></body></html>")
+    }
+
+    #[test]
+    fn test_submit() {
+        let secs = Duration::from_secs;
+        let cases = [
+            (
+                "That's the right answer!  You are <span class=\"day-success\">one gold star</span> closer.",
+                Verdict::Correct,
+                MIN_INTERVAL,
+            ),
+            (
+                "That's not the right answer; your answer is too high.  If you're stuck, make sure \
+                 you're using the full input data. Please wait one minute before trying again. \
+                 <a href=\"/2023/day/1\">[Return to Day 1]</a>",
+                Verdict::TooHigh,
+                secs(60),
+            ),
+            (
+                "That's not the right answer; your answer is too low.  Please wait 5 minutes \
+                 before trying again.",
+                Verdict::TooLow,
+                secs(300),
+            ),
+            ("That's not the right answer.", Verdict::Incorrect, secs(60)),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer \
+                 before trying again.  You have 1m 5s left to wait.",
+                Verdict::Wait(secs(65)),
+                secs(65),
+            ),
+            (
+                "You don't seem to be solving the right level.  Did you already complete it?",
+                Verdict::AlreadySolved,
+                MIN_INTERVAL,
+            ),
+        ];
+        for (text, verdict, cooldown) in cases {
+            assert_eq!(
+                parse_response(&page(text)),
+                Ok(Response { verdict, cooldown }),
+                "{text}"
+            );
+        }
+        assert!(parse_response("<html>login</html>").is_err());
+
+        let server = MockServer::start(vec![(200, page("That's the right answer!"))]);
+        let client = Client::new(server.url(), Some("s3cret".to_string()));
+        let response = submit(&client, 3, 2, "4361").unwrap();
+        assert_eq!(response.verdict, Verdict::Correct);
+        let request = &server.requests()[0];
+        assert!(request.starts_with("POST /2023/day/3/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=s3cret\r\n"));
+        assert!(request.ends_with("\r\n\r\nlevel=2&answer=4361"));
+
+        let path = env::temp_dir().join(format!("adv2023-cooldown-{}", std::process::id()));
+        let throttle = Throttle::new(&path);
+        let now = UNIX_EPOCH + secs(1_700_000_000);
+        assert_eq!(throttle.remaining(now), Duration::ZERO);
+        throttle.record(now, secs(60)).unwrap();
+        assert_eq!(throttle.remaining(now + secs(15)), secs(45));
+        assert_eq!(throttle.remaining(now + secs(61)), Duration::ZERO);
+        throttle.record(now, Duration::ZERO).unwrap();
+        assert_eq!(throttle.remaining(now), MIN_INTERVAL);
+        fs::remove_file(&path).unwrap();
+    }
+}